@@ -1,8 +1,10 @@
+use std::collections::VecDeque;
+
 use aoc_framework::*;
 
 pub struct Day01;
 
-impl_day!(Day01::{part1, part2}: 2023[1], r"
+impl_day_with_examples!(Day01::{part1, part2}: 2023[1], r"
 1abc2
 pqr3stu8vwx
 a1b2c3d4e5f
@@ -18,60 +20,219 @@ zoneight234
 7pqrstsixteen
 ");
 
-fn find_digit(mut it: impl Iterator<Item = u8>) -> Option<u8> {
-    it.find(|b| b.is_ascii_digit()).map(|b| b - b'0')
+const DIGITS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+const ENGLISH: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+const FRENCH: [&str; 10] = [
+    "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+];
+
+const GERMAN: [&str; 10] = [
+    "null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+];
+
+fn vocabulary(name: &str) -> Option<&'static [&'static str; 10]> {
+    match name {
+        "digits" => Some(&DIGITS),
+        "english" => Some(&ENGLISH),
+        "french" => Some(&FRENCH),
+        "german" => Some(&GERMAN),
+        _ => None,
+    }
 }
 
-#[aoc(part = 1, example = 142)]
-fn part1(input: impl Iterator<Item = String>) -> u64 {
-    input
-        .map(|line| {
-            find_digit(line.bytes())
-                .into_iter()
-                .chain(find_digit(line.bytes().rev()))
-                .fold(0, |acc, val| acc * 10 + val as u64)
-        })
-        .sum()
+#[derive(Clone, Copy)]
+struct Token {
+    start: usize,
+    len: usize,
+    digit: u8,
 }
 
-fn match_digit(s: &str) -> Option<u8> {
-    if let Some(b @ b'0'..=b'9') = s.as_bytes().first() {
-        return Some(b - b'0');
-    }
-    const NUMS: [&str; 10] = [
-        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-    ];
-    for (d, num) in NUMS.iter().enumerate() {
-        if s.starts_with(num) {
-            return Some(d as u8);
-        }
-    }
-    None
+/// Aho-Corasick automaton over bytes, reporting every word ending at each position, so
+/// overlapping words like `twone` yield both digits.
+struct Matcher {
+    next: Vec<[u32; 256]>,
+    /// `(word length, digit)` for each word that ends in this state.
+    outputs: Vec<Vec<(usize, u8)>>,
 }
 
-#[aoc(part = 2, example = 281)]
-fn part2(input: impl Iterator<Item = String>) -> u64 {
-    input
-        .map(|line| {
-            let mut l = None;
-            let mut r = None;
-            for i in 0..line.len() {
-                if l.is_none() {
-                    if let Some(n) = match_digit(&line[i..]) {
-                        l = Some(n as u64);
-                    }
-                }
-                if r.is_none() {
-                    if let Some(n) = match_digit(&line[(line.len() - 1 - i)..]) {
-                        r = Some(n as u64);
-                    }
+impl Matcher {
+    fn new<'a>(words: impl IntoIterator<Item = (&'a str, u8)>) -> Self {
+        let mut m = Matcher {
+            next: vec![[0; 256]],
+            outputs: vec![vec![]],
+        };
+        // build the trie first, 0 marks a missing edge since nothing leads back to the root
+        for (word, digit) in words.into_iter().filter(|(word, _)| !word.is_empty()) {
+            let mut state = 0;
+            for &b in word.as_bytes() {
+                if m.next[state][b as usize] == 0 {
+                    m.next[state][b as usize] = m.next.len() as u32;
+                    m.next.push([0; 256]);
+                    m.outputs.push(vec![]);
                 }
+                state = m.next[state][b as usize] as usize;
+            }
+            m.outputs[state].push((word.len(), digit));
+        }
 
-                if let (Some(l), Some(r)) = (l, r) {
-                    return l * 10 + r;
+        // then fill in missing edges from the failure links, shallowest states first
+        let mut fail = vec![0; m.next.len()];
+        let mut queue = m.next[0]
+            .iter()
+            .filter(|&&s| s != 0)
+            .map(|&s| s as usize)
+            .collect::<VecDeque<_>>();
+        while let Some(state) = queue.pop_front() {
+            let inherited = m.outputs[fail[state]].clone();
+            m.outputs[state].extend(inherited);
+            for b in 0..256 {
+                let fallback = m.next[fail[state]][b];
+                match m.next[state][b] as usize {
+                    0 => m.next[state][b] = fallback,
+                    child => {
+                        fail[child] = fallback as usize;
+                        queue.push_back(child);
+                    }
                 }
             }
-            0
+        }
+        m
+    }
+
+    /// Matches the plain digits plus one word per digit value.
+    fn with_words(words: &[&str; 10]) -> Self {
+        Self::new(
+            DIGITS
+                .iter()
+                .chain(words)
+                .zip((0..10).cycle())
+                .map(|(w, d)| (*w, d))
+                .unique_by(|(w, _)| *w),
+        )
+    }
+
+    fn tokens<'a>(&'a self, line: &'a str) -> impl Iterator<Item = Token> + 'a {
+        line.bytes()
+            .scan(0, |state, b| {
+                *state = self.next[*state][b as usize] as usize;
+                Some(*state)
+            })
+            .enumerate()
+            .flat_map(move |(i, state)| {
+                self.outputs[state].iter().map(move |&(len, digit)| Token {
+                    start: i + 1 - len,
+                    len,
+                    digit,
+                })
+            })
+    }
+
+    /// First and last token by starting position.
+    fn pick(&self, line: &str) -> Option<(Token, Token)> {
+        self.tokens(line).minmax_by_key(|t| t.start).into_option()
+    }
+
+    fn calibration_value(&self, line: &str) -> u64 {
+        self.pick(line).map_or(0, |(first, last)| {
+            first.digit as u64 * 10 + last.digit as u64
         })
-        .sum()
+    }
+}
+
+/// Lists every token found on each line along with the two picked for the calibration value.
+pub fn list_tokens(input: &str, language: &str) -> anyhow::Result<String> {
+    let Some(words) = vocabulary(language) else {
+        anyhow::bail!(
+            "unknown vocabulary {language:?}, expected digits, english, french or german"
+        );
+    };
+    let matcher = Matcher::with_words(words);
+    let mut out = String::new();
+    for line in input.lines() {
+        let found = matcher
+            .tokens(line)
+            .map(|t| &line[t.start..t.start + t.len])
+            .join(" ");
+        let picked = match matcher.pick(line) {
+            Some((first, last)) => format!(
+                "{}, {}",
+                &line[first.start..first.start + first.len],
+                &line[last.start..last.start + last.len]
+            ),
+            None => "nothing".to_string(),
+        };
+        out += &format!(
+            "{line}: [{found}] -> {picked} = {}\n",
+            matcher.calibration_value(line)
+        );
+    }
+    Ok(out)
+}
+
+#[aoc(part = 1, example = 142)]
+pub fn part1(input: impl Iterator<Item = String>) -> u64 {
+    let matcher = Matcher::new(DIGITS.iter().copied().zip(0..));
+    input.map(|line| matcher.calibration_value(&line)).sum()
+}
+
+#[aoc(part = 2, example = 281)]
+pub fn part2(input: impl Iterator<Item = String>) -> u64 {
+    let matcher = Matcher::with_words(&ENGLISH);
+    input.map(|line| matcher.calibration_value(&line)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(language: &str, line: &str) -> u64 {
+        Matcher::with_words(vocabulary(language).unwrap()).calibration_value(line)
+    }
+
+    #[test]
+    fn english() {
+        assert_eq!(value("english", "abcone2threexyz"), 13);
+        assert_eq!(value("english", "7pqrstsixteen"), 76);
+        assert_eq!(value("english", "nothing"), 0);
+    }
+
+    #[test]
+    fn french() {
+        assert_eq!(value("french", "deux1cinq"), 25);
+        assert_eq!(value("french", "zérounsept"), 7);
+        assert_eq!(value("french", "xneufx"), 99);
+    }
+
+    #[test]
+    fn german() {
+        assert_eq!(value("german", "fünfacht"), 58);
+        assert_eq!(value("german", "nullzwei"), 2);
+        assert_eq!(value("german", "x3drei"), 33);
+    }
+
+    #[test]
+    fn digits_only() {
+        assert_eq!(value("digits", "one2three4five"), 24);
+        assert!(vocabulary("klingon").is_none());
+        assert!(list_tokens("1", "klingon").is_err());
+    }
+
+    #[test]
+    fn overlapping_words() {
+        assert_eq!(value("english", "twone"), 21);
+        assert_eq!(value("english", "eightwo"), 82);
+        assert_eq!(value("english", "oneight"), 18);
+        assert_eq!(value("english", "threeight"), 38);
+        assert_eq!(value("french", "cinquatre"), 54);
+        assert_eq!(value("french", "huitrois"), 83);
+        assert_eq!(value("german", "einsieben"), 17);
+        assert_eq!(
+            list_tokens("xtwone3", "english").unwrap(),
+            "xtwone3: [two one 3] -> two, 3 = 23\n"
+        );
+    }
 }
@@ -1,8 +1,8 @@
-use aoc_framework::*;
+use aoc_framework::{anyhow::Context, *};
 
 pub struct Day02;
 
-impl_day!(Day02::{part1, part2}: 2023[2], r"
+impl_day_with_examples!(Day02::{part1, part2}: 2023[2], r"
 Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
 Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
@@ -10,64 +10,148 @@ Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
 ");
 
-const MAX: [u64; 3] = [12, 13, 14];
+const COLOURS: [&str; 3] = ["red", "green", "blue"];
+const BAG: [u64; 3] = [12, 13, 14];
 
-fn color_index(c: &str) -> usize {
-    match c {
-        "red" => 0,
-        "green" => 1,
-        _ => 2,
+/// Cube counts are stored per handful in the order of the colours the game was parsed with.
+struct Game {
+    id: u64,
+    handfuls: Vec<Vec<u64>>,
+}
+
+impl Game {
+    fn parse(line: &str, colours: &[&str]) -> anyhow::Result<Game> {
+        let (header, contents) = line.split_once(": ").context("missing ': '")?;
+        let id = header
+            .strip_prefix("Game ")
+            .context("missing 'Game' header")?
+            .parse()?;
+        let handfuls = contents
+            .split("; ")
+            .map(|handful| {
+                let mut counts = vec![0; colours.len()];
+                for subset in handful.split(", ") {
+                    let (n, colour) = subset
+                        .split_once(' ')
+                        .with_context(|| format!("expected '<count> <colour>', got {subset:?}"))?;
+                    let Some(c) = colours.iter().position(|&c| c == colour) else {
+                        anyhow::bail!("unknown colour {colour:?}");
+                    };
+                    counts[c] += n.parse::<u64>()?;
+                }
+                Ok(counts)
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Game { id, handfuls })
+    }
+
+    /// Fewest cubes of each colour that make every handful possible.
+    fn minimal_bag(&self) -> Vec<u64> {
+        let colours = self.handfuls.first().map_or(0, Vec::len);
+        (0..colours)
+            .map(|c| self.handfuls.iter().map(|h| h[c]).max().unwrap_or(0))
+            .collect()
+    }
+
+    fn fits(&self, bag: &[u64]) -> bool {
+        self.minimal_bag().iter().zip(bag).all(|(n, max)| n <= max)
     }
 }
 
-fn parse_handful(s: &str) -> [u64; 3] {
-    let mut res = [0; 3];
-    s.split(", ")
-        .flat_map(|subset| {
-            let (n, color) = subset.split_once(' ')?;
-            Some((n.parse::<u64>().ok()?, color_index(color)))
-        })
-        .for_each(|(n, color)| res[color] += n);
-    res
+fn parse_games<'a>(
+    input: impl IntoIterator<Item = &'a str>,
+    colours: &[&str],
+) -> anyhow::Result<Vec<Game>> {
+    input
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| Game::parse(line, colours).with_context(|| format!("line {}", i + 1)))
+        .collect()
 }
 
-fn parse_game(g: &str) -> [u64; 3] {
-    g.split("; ")
-        .map(parse_handful)
-        .fold([0; 3], |[acc_r, acc_g, acc_b], [r, g, b]| {
-            [acc_r.max(r), acc_g.max(g), acc_b.max(b)]
+/// Parses a bag configuration like "12 red, 13 green, 14 blue" into its colours and counts.
+fn parse_bag(spec: &str) -> anyhow::Result<(Vec<&str>, Vec<u64>)> {
+    spec.split(',')
+        .map(|subset| {
+            let (n, colour) = subset
+                .trim()
+                .split_once(' ')
+                .with_context(|| format!("expected '<count> <colour>', got {subset:?}"))?;
+            Ok((colour, n.parse::<u64>()?))
         })
+        .collect()
 }
 
-fn parse_header(line: &str) -> Option<(u64, &str)> {
-    let (header, contents) = line.split_once(": ")?;
-    let (_, n) = header.split_once(' ')?;
-    Some((n.parse().ok()?, contents))
+/// Lists the feasible games for the given bag along with the sum of their ids. The bag's
+/// colours are the only ones games may use.
+pub fn feasible_games(input: &str, bag: &str) -> anyhow::Result<String> {
+    let (colours, counts) = parse_bag(bag)?;
+    let games = parse_games(input.lines(), &colours)?;
+    let feasible = games.iter().filter(|g| g.fits(&counts)).map(|g| g.id);
+    Ok(format!(
+        "feasible: {}\nsum of ids: {}\n",
+        feasible.clone().join(", "),
+        feasible.sum::<u64>()
+    ))
 }
 
-#[aoc(part = 1, example = 8)]
-fn part1(input: impl Iterator<Item = String>) -> u64 {
-    input
-        .filter_map(|line| {
-            let (n, contents) = parse_header(&line)?;
-            contents
-                .split("; ")
-                .map(parse_handful)
-                .all(|res| res.iter().zip(MAX).all(|(&val, max)| val <= max))
-                .then_some(n)
+/// Lists the smallest possible bag for each game along with its power.
+pub fn minimal_bags(input: &str) -> anyhow::Result<String> {
+    let games = parse_games(input.lines(), &COLOURS)?;
+    Ok(games
+        .iter()
+        .map(|g| {
+            let bag = g.minimal_bag();
+            format!(
+                "Game {}: {} (power {})\n",
+                g.id,
+                bag.iter()
+                    .zip(COLOURS)
+                    .map(|(n, c)| format!("{n} {c}"))
+                    .join(", "),
+                bag.iter().product::<u64>()
+            )
         })
-        .sum()
+        .collect())
 }
 
-fn parse_line(line: String) -> Option<[u64; 3]> {
-    let (_, game) = parse_header(&line)?;
-    Some(parse_game(game))
+fn games(input: impl Iterator<Item = String>) -> anyhow::Result<Vec<Game>> {
+    let lines = input.collect_vec();
+    parse_games(lines.iter().map(String::as_str), &COLOURS)
+}
+
+#[aoc(part = 1, example = 8)]
+pub fn part1(input: impl Iterator<Item = String>) -> anyhow::Result<u64> {
+    Ok(games(input)?
+        .iter()
+        .filter(|g| g.fits(&BAG))
+        .map(|g| g.id)
+        .sum())
 }
 
 #[aoc(part = 2, example = 2286)]
-fn part2(input: impl Iterator<Item = String>) -> u64 {
-    input
-        .flat_map(parse_line)
-        .map(|mins| mins.iter().product::<u64>())
-        .sum()
+pub fn part2(input: impl Iterator<Item = String>) -> anyhow::Result<u64> {
+    Ok(games(input)?
+        .iter()
+        .map(|g| g.minimal_bag().iter().product::<u64>())
+        .sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_colour_is_rejected() {
+        let input = ["Game 1: 3 blue, 4 purple; 1 red".to_string()];
+        let err = part1(input.into_iter()).unwrap_err();
+        assert!(format!("{err:#}").contains("unknown colour \"purple\""));
+    }
+
+    #[test]
+    fn colour_outside_the_bag_is_rejected() {
+        let input = "Game 1: 3 blue, 4 red";
+        assert!(feasible_games(input, "12 red, 13 green").is_err());
+        assert!(feasible_games(input, "12 red, 13 blue").is_ok());
+    }
 }
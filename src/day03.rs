@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use aoc_framework::{point::Point2, *};
 use grid::Grid;
 
@@ -5,7 +7,7 @@ type GridView<'a> = grid::GridView<'a, u8, 2>;
 
 pub struct Day03;
 
-impl_day!(Day03::{part1, part2}: 2023[3], r"
+impl_day_with_examples!(Day03::{part1, part2}: 2023[3], r"
 467..114..
 ...*......
 ..35..633.
@@ -18,61 +20,240 @@ impl_day!(Day03::{part1, part2}: 2023[3], r"
 .664.598..
 ");
 
-fn extract_number(g: &mut GridView<'_>, mut pos: Point2) -> u64 {
-    let dx = Point2::new(1, 0);
-    while let Some(b'0'..=b'9') = g.get(pos - dx) {
-        pos -= dx;
-    }
-    let mut out = 0;
-    while let Some(b @ b'0'..=b'9') = g.get(pos) {
-        out = out * 10 + (b - b'0') as u64;
-        g.set(pos, b' ');
-        pos += dx;
-    }
-    out
+struct Number {
+    value: u64,
+    /// Leftmost digit, the number continues `len` cells to the right.
+    start: Point2,
+    len: usize,
+    /// Indices into `Schematic::symbols`.
+    symbols: Vec<usize>,
 }
 
-#[aoc(part = 1, example = 4361)]
-fn part1(input: Vec<u8>) -> u64 {
-    let mut grid = Grid::from_bytes(input);
-    let mut sum = 0;
-    for pos in grid.points_iter() {
-        match grid.get(pos) {
-            None | Some(b'.' | b'0'..=b'9') => continue,
-            Some(_) => {}
+struct Symbol {
+    pos: Point2,
+    byte: u8,
+    /// Indices into `Schematic::numbers`.
+    numbers: Vec<usize>,
+}
+
+/// Every number and symbol in the engine schematic, linked to their neighbours.
+struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+}
+
+fn is_symbol(b: u8) -> bool {
+    b != b'.' && !b.is_ascii_digit()
+}
+
+impl Schematic {
+    fn parse(input: Vec<u8>) -> Self {
+        let grid: GridView = Grid::from_bytes(input);
+        let mut symbols = vec![];
+        let mut symbol_at = HashMap::new();
+        for pos in grid.points_iter() {
+            if is_symbol(grid[pos]) {
+                symbol_at.insert(pos.0, symbols.len());
+                symbols.push(Symbol {
+                    pos,
+                    byte: grid[pos],
+                    numbers: vec![],
+                });
+            }
         }
 
-        for neighbor in pos.neighbors_diag() {
-            if grid.get(neighbor).is_some_and(|b| b.is_ascii_digit()) {
-                sum += extract_number(&mut grid, neighbor);
+        let mut numbers: Vec<Number> = vec![];
+        let dx = Point2::new(1, 0);
+        for pos in grid.points_iter() {
+            let is_digit = |p| grid.get(p).is_some_and(u8::is_ascii_digit);
+            if !is_digit(pos) || is_digit(pos - dx) {
+                continue;
+            }
+            let digits = (0..)
+                .map(|i| pos + dx * i)
+                .take_while(|&p| is_digit(p))
+                .collect_vec();
+            let value = digits
+                .iter()
+                .fold(0, |acc, &p| acc * 10 + (grid[p] - b'0') as u64);
+            let adjacent = digits
+                .iter()
+                .flat_map(|p| p.neighbors_diag())
+                .filter_map(|p| symbol_at.get(&p.0).copied())
+                .unique()
+                .collect_vec();
+            for &s in &adjacent {
+                symbols[s].numbers.push(numbers.len());
             }
+            numbers.push(Number {
+                value,
+                start: pos,
+                len: digits.len(),
+                symbols: adjacent,
+            });
         }
+        Schematic { numbers, symbols }
+    }
+
+    /// Numbers touching at least one symbol.
+    fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers.iter().filter(|n| !n.symbols.is_empty())
+    }
+
+    fn unattached(&self) -> impl Iterator<Item = &Number> {
+        self.numbers.iter().filter(|n| n.symbols.is_empty())
+    }
+
+    fn adjacent_to(&self, symbol: u8) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .filter(move |n| n.symbols.iter().any(|&s| self.symbols[s].byte == symbol))
+    }
+
+    /// `symbol` cells touching exactly `count` numbers.
+    fn gears(&self, symbol: u8, count: usize) -> impl Iterator<Item = &Symbol> {
+        self.symbols
+            .iter()
+            .filter(move |s| s.byte == symbol && s.numbers.len() == count)
+    }
+
+    fn describe(&self, n: &Number) -> String {
+        let symbols = n
+            .symbols
+            .iter()
+            .map(|&s| {
+                let Symbol { pos, byte, .. } = &self.symbols[s];
+                format!("{} at {:?}", *byte as char, pos.0)
+            })
+            .join(", ");
+        format!(
+            "{} at {:?} (len {}): [{symbols}]",
+            n.value, n.start.0, n.len
+        )
     }
-    sum
+}
+
+/// Lists every number with its span and adjacent symbols.
+pub fn list_numbers(input: Vec<u8>) -> String {
+    let schematic = Schematic::parse(input);
+    schematic
+        .numbers
+        .iter()
+        .map(|n| schematic.describe(n) + "\n")
+        .collect()
+}
+
+/// Lists the numbers adjacent to `symbol`, or the unattached ones if no symbol is given.
+pub fn list_adjacent(input: Vec<u8>, symbol: Option<u8>) -> String {
+    let schematic = Schematic::parse(input);
+    let numbers = match symbol {
+        Some(symbol) => schematic.adjacent_to(symbol).collect_vec(),
+        None => schematic.unattached().collect_vec(),
+    };
+    let sum = numbers.iter().map(|n| n.value).sum::<u64>();
+    numbers
+        .iter()
+        .map(|n| schematic.describe(n) + "\n")
+        .chain([format!("{} numbers, sum {sum}\n", numbers.len())])
+        .collect()
+}
+
+/// Lists the `symbol` cells touching exactly `count` numbers along with their ratios.
+pub fn list_gears(input: Vec<u8>, symbol: u8, count: usize) -> String {
+    let schematic = Schematic::parse(input);
+    let mut total = 0;
+    let mut out = String::new();
+    for gear in schematic.gears(symbol, count) {
+        let values = gear
+            .numbers
+            .iter()
+            .map(|&n| schematic.numbers[n].value)
+            .collect_vec();
+        let ratio = values.iter().product::<u64>();
+        total += ratio;
+        out += &format!(
+            "{:?}: {} = {ratio}\n",
+            gear.pos.0,
+            values.iter().join(" * ")
+        );
+    }
+    out + &format!("total {total}\n")
+}
+
+#[aoc(part = 1, example = 4361)]
+pub fn part1(input: Vec<u8>) -> u64 {
+    Schematic::parse(input)
+        .part_numbers()
+        .map(|n| n.value)
+        .sum()
 }
 
 #[aoc(part = 2, example = 467835)]
-fn part2(input: Vec<u8>) -> u64 {
-    let mut grid = Grid::from_bytes(input);
-    let mut sum = 0;
-    let mut nums = Vec::with_capacity(2);
-    let mut pos = 0;
-    while pos < grid.data().len() {
-        let Some(off) = grid.data().iter().skip(pos).position(|&b| b == b'*') else {
-            break;
-        };
-        pos += off;
-        let pt = grid.offset_to_point(pos);
-        pos += 1;
-        nums.clear();
-        for neighbor in pt.neighbors_diag() {
-            if grid.get(neighbor).is_some_and(|b| b.is_ascii_digit()) {
-                nums.push(extract_number(&mut grid, neighbor));
-            }
-        }
-        if nums.len() == 2 {
-            sum += nums.iter().product::<u64>();
-        }
+pub fn part2(input: Vec<u8>) -> u64 {
+    let schematic = Schematic::parse(input);
+    schematic
+        .gears(b'*', 2)
+        .map(|gear| {
+            gear.numbers
+                .iter()
+                .map(|&n| schematic.numbers[n].value)
+                .product::<u64>()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Schematic {
+        Schematic::parse(EXAMPLES[0].strip_prefix('\n').unwrap().as_bytes().to_vec())
+    }
+
+    fn values<'a>(numbers: impl Iterator<Item = &'a Number>) -> Vec<u64> {
+        numbers.map(|n| n.value).collect()
+    }
+
+    #[test]
+    fn numbers_end_with_their_row() {
+        let schematic = Schematic::parse(b"..12\n34..\n".to_vec());
+        let spans = schematic
+            .numbers
+            .iter()
+            .map(|n| (n.value, n.start.0, n.len))
+            .collect_vec();
+        assert_eq!(spans, [(12, [2, 0], 2), (34, [0, 1], 2)]);
+    }
+
+    #[test]
+    fn adjacent_to_other_symbols() {
+        let schematic = example();
+        assert_eq!(values(schematic.adjacent_to(b'#')), [633]);
+        assert_eq!(values(schematic.adjacent_to(b'+')), [592]);
+        assert_eq!(values(schematic.adjacent_to(b'$')), [664]);
+        assert_eq!(values(schematic.adjacent_to(b'%')), []);
+    }
+
+    #[test]
+    fn gears_with_other_counts() {
+        let schematic = example();
+        let lone = schematic.gears(b'*', 1).map(|s| s.pos.0).collect_vec();
+        assert_eq!(lone, [[3, 4]]);
+
+        let schematic = Schematic::parse(b"1.2\n.*.\n3..\n".to_vec());
+        let gears = schematic.gears(b'*', 3).collect_vec();
+        assert_eq!(gears.len(), 1);
+        assert_eq!(schematic.gears(b'*', 2).count(), 0);
+        let ratio = gears[0]
+            .numbers
+            .iter()
+            .map(|&n| schematic.numbers[n].value)
+            .product::<u64>();
+        assert_eq!(ratio, 6);
+    }
+
+    #[test]
+    fn unattached_numbers() {
+        assert_eq!(values(example().unattached()), [114, 58]);
     }
-    sum
 }
@@ -1,8 +1,8 @@
-use aoc_framework::*;
+use aoc_framework::{anyhow::Context, *};
 
 pub struct Day04;
 
-impl_day!(Day04::{part1, part2}: 2023[4], r"
+impl_day_with_examples!(Day04::{part1, part2}: 2023[4], r"
 Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
 Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
 Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
@@ -11,59 +11,171 @@ Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
 ");
 
-fn to_mask(numbers: &str) -> u128 {
-    let mut mask = 0u128;
-    let mut it = numbers.bytes();
-    let mut num = 0;
-    loop {
-        match it.next() {
-            None => {
-                mask |= 1 << num;
-                break;
-            }
-            Some(b' ') if num != 0 => {
-                mask |= 1 << num;
-                num = 0;
+/// Both lists are sorted and deduplicated.
+struct Card {
+    id: u64,
+    winning: Vec<u64>,
+    numbers: Vec<u64>,
+}
+
+/// Numbers may be separated by any mix of whitespace and commas.
+fn parse_numbers(s: &str) -> anyhow::Result<Vec<u64>> {
+    let mut numbers = s
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|n| !n.is_empty())
+        .map(|n| n.parse().with_context(|| format!("invalid number {n:?}")))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    numbers.sort_unstable();
+    numbers.dedup();
+    Ok(numbers)
+}
+
+fn to_mask(numbers: &[u64]) -> u128 {
+    numbers.iter().fold(0, |mask, n| mask | 1 << n)
+}
+
+impl Card {
+    fn parse(line: &str) -> anyhow::Result<Card> {
+        let (header, contents) = line.split_once(':').context("missing ':'")?;
+        let id = header
+            .trim()
+            .strip_prefix("Card")
+            .context("missing 'Card' header")?
+            .trim()
+            .parse()?;
+        let (winning, numbers) = contents.split_once('|').context("missing '|'")?;
+        Ok(Card {
+            id,
+            winning: parse_numbers(winning)?,
+            numbers: parse_numbers(numbers)?,
+        })
+    }
+
+    fn matches(&self) -> usize {
+        let small = |ns: &[u64]| ns.last().is_none_or(|&n| n < 128);
+        if small(&self.winning) && small(&self.numbers) {
+            return (to_mask(&self.winning) & to_mask(&self.numbers)).count_ones() as usize;
+        }
+        // merge the two sorted lists
+        let (mut w, mut n) = (
+            self.winning.iter().peekable(),
+            self.numbers.iter().peekable(),
+        );
+        let mut count = 0;
+        while let (Some(a), Some(b)) = (w.peek(), n.peek()) {
+            match a.cmp(b) {
+                std::cmp::Ordering::Less => _ = w.next(),
+                std::cmp::Ordering::Greater => _ = n.next(),
+                std::cmp::Ordering::Equal => {
+                    count += 1;
+                    w.next();
+                    n.next();
+                }
             }
-            Some(b @ b'0'..=b'9') => num = num * 10 + (b - b'0'),
-            _ => {}
+        }
+        count
+    }
+
+    /// One point for the first match, doubled for every further one, if it fits in 64 bits.
+    fn score(&self) -> Option<u64> {
+        match self.matches() {
+            0 => Some(0),
+            w => 1u64.checked_shl(u32::try_from(w - 1).ok()?),
+        }
+    }
+}
+
+fn parse_cards<'a>(input: impl IntoIterator<Item = &'a str>) -> anyhow::Result<Vec<Card>> {
+    input
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| Card::parse(line).with_context(|| format!("line {}", i + 1)))
+        .collect()
+}
+
+/// Number of copies of each card, along with the cards each one was won from.
+fn copies(cards: &[Card]) -> (Vec<u64>, Vec<Vec<usize>>) {
+    let mut counts = vec![1; cards.len()];
+    let mut won_from = vec![vec![]; cards.len()];
+    for (i, card) in cards.iter().enumerate() {
+        for j in (i + 1..cards.len()).take(card.matches()) {
+            counts[j] += counts[i];
+            won_from[j].push(i);
         }
     }
-    mask
+    (counts, won_from)
 }
 
-fn num_winning_numbers(winning: &str, numbers: &str) -> usize {
-    let w = to_mask(winning);
-    let nums = to_mask(numbers);
-    (w & nums).count_ones() as usize
+/// Lists the copies of each card and how many of them each earlier card won.
+pub fn list_copies(input: &str) -> anyhow::Result<String> {
+    let cards = parse_cards(input.lines())?;
+    let (counts, won_from) = copies(&cards);
+    let mut out = String::new();
+    for (i, card) in cards.iter().enumerate() {
+        let sources = won_from[i]
+            .iter()
+            .map(|&j| format!("{} from card {}", counts[j], cards[j].id))
+            .join(", ");
+        out += &format!(
+            "Card {}: {} matches, {} copies = 1 original{}{sources}\n",
+            card.id,
+            card.matches(),
+            counts[i],
+            if sources.is_empty() { "" } else { ", " }
+        );
+    }
+    Ok(out + &format!("total {}\n", counts.iter().sum::<u64>()))
+}
+
+fn cards(input: impl Iterator<Item = String>) -> anyhow::Result<Vec<Card>> {
+    let lines = input.collect_vec();
+    parse_cards(lines.iter().map(String::as_str))
 }
 
 #[aoc(part = 1, example = 13)]
-fn part1(input: impl Iterator<Item = String>) -> u64 {
-    input
-        .flat_map(|line| {
-            let (_, card) = line.split_once(": ")?;
-            let (winning, numbers) = card.split_once(" | ")?;
-            let w = num_winning_numbers(winning, numbers);
-            Some(if w == 0 { 0 } else { 1 << (w - 1) })
-        })
-        .sum()
+pub fn part1(input: impl Iterator<Item = String>) -> anyhow::Result<u64> {
+    cards(input)?
+        .iter()
+        .try_fold(0u64, |sum, card| sum.checked_add(card.score()?))
+        .context("total score doesn't fit in 64 bits")
 }
 
 #[aoc(part = 2, example = 30)]
-fn part2(input: Vec<String>) -> u64 {
-    let mut counts = vec![1; input.len()];
-    input.into_iter().enumerate().for_each(|(i, line)| {
-        let (winning, numbers) = line
-            .split_once(": ")
-            .and_then(|(_, numbers)| numbers.split_once(" | "))
-            .unwrap_or_default();
-        let c = counts[i];
-        counts
-            .iter_mut()
-            .skip(i + 1)
-            .take(num_winning_numbers(winning, numbers))
-            .for_each(|count| *count += c)
-    });
-    counts.into_iter().sum()
+pub fn part2(input: impl Iterator<Item = String>) -> anyhow::Result<u64> {
+    Ok(copies(&cards(input)?).0.into_iter().sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(winning: &[u64], numbers: &[u64]) -> String {
+        format!(
+            "Card 1: {} | {}",
+            winning.iter().join(" "),
+            numbers.iter().join(" ")
+        )
+    }
+
+    #[test]
+    fn large_numbers() {
+        let input = [card(&[5, 130, 1000], &[130, 7, 1000, 5, 4000])];
+        assert_eq!(part1(input.into_iter()).unwrap(), 4);
+        let input = [card(&[200, 127], &[127, 128])];
+        assert_eq!(part1(input.into_iter()).unwrap(), 1);
+    }
+
+    #[test]
+    fn many_winners() {
+        let winners = (1..=65).collect_vec();
+        let input = [card(&winners[..64], &winners[..64])];
+        assert_eq!(part1(input.into_iter()).unwrap(), 1 << 63);
+        let input = [card(&winners, &winners)];
+        assert!(part1(input.into_iter()).is_err());
+        let input = [
+            card(&winners[..64], &winners[..64]),
+            card(&winners[..64], &winners[..64]),
+        ];
+        assert!(part1(input.into_iter()).is_err());
+    }
 }
@@ -1,10 +1,12 @@
 use std::collections::VecDeque;
 
-use aoc_framework::*;
+use aoc_framework::{anyhow::Context, *};
+
+use crate::solution::{parse_and_solve, Solution};
 
 pub struct Day05;
 
-impl_day!(Day05::{part1, part2}: 2023[5], r"
+impl_day_with_examples!(Day05::{part1, part2}: 2023[5], r"
 seeds: 79 14 55 13
 
 seed-to-soil map:
@@ -130,73 +132,87 @@ impl RangeList {
     }
 }
 
-fn parse_range(line: &str) -> Range {
+fn parse_range(line: &str) -> anyhow::Result<Range> {
     let (dst, src, len) = line
         .split(' ')
-        .map(|num| num.parse::<usize>().unwrap())
-        .tuples()
-        .next()
-        .unwrap();
-    Range { src, dst, len }
+        .map(|num| num.parse::<usize>())
+        .collect_tuple()
+        .with_context(|| format!("expected three numbers, got {line:?}"))?;
+    Ok(Range {
+        src: src?,
+        dst: dst?,
+        len: len?,
+    })
 }
 
-#[aoc(part = 1, example = 35)]
-fn part1(mut input: impl Iterator<Item = String>) -> u64 {
-    let seeds = input
-        .next()
-        .unwrap()
-        .split(' ')
-        .skip(1)
-        .map(|n| n.parse::<usize>().unwrap())
-        .collect_vec();
-    input.next();
-    let mut range_lists = Vec::new();
-    while let Some(_) = input.next() {
-        let list = (&mut input)
-            .take_while(|ln| !ln.is_empty())
-            .map(|ln| parse_range(&ln))
-            .collect::<Vec<_>>();
-        range_lists.push(RangeList(list));
-    }
-    seeds
-        .into_iter()
-        .map(|seed| range_lists.iter().fold(seed, |n, lst| lst.map(n)))
-        .min()
-        .unwrap() as u64
+pub struct Almanac {
+    seeds: Vec<usize>,
+    maps: Vec<RangeList>,
 }
 
-#[aoc(part = 2, example = 46)]
-fn part2(mut input: impl Iterator<Item = String>) -> u64 {
-    let seeds = input
-        .next()
-        .unwrap()
-        .split(' ')
-        .skip(1)
-        .map(|n| n.parse::<usize>().unwrap())
-        .tuples()
-        .map(|(src, len)| SeedRange { src, len })
-        .collect_vec();
-    input.next();
-    let mut range_lists = Vec::new();
-    while let Some(_) = input.next() {
-        let list = (&mut input)
-            .take_while(|ln| !ln.is_empty())
-            .map(|ln| parse_range(&ln))
-            .collect::<Vec<_>>();
-        range_lists.push(RangeList(list));
+impl Solution for Day05 {
+    type Parsed = Almanac;
+    type Output = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Almanac> {
+        let mut lines = input.lines();
+        let seeds = lines
+            .next()
+            .and_then(|ln| ln.strip_prefix("seeds:"))
+            .context("missing seeds")?
+            .split_whitespace()
+            .map(|n| n.parse::<usize>())
+            .collect::<Result<_, _>>()?;
+        lines.next();
+        let mut maps = Vec::new();
+        while lines.next().is_some() {
+            let list = (&mut lines)
+                .take_while(|ln| !ln.is_empty())
+                .map(parse_range)
+                .collect::<anyhow::Result<_>>()?;
+            maps.push(RangeList(list));
+        }
+        Ok(Almanac { seeds, maps })
     }
-    seeds
-        .into_iter()
-        .flat_map(|seed_range| {
-            range_lists.iter().fold(vec![seed_range], |ranges, lst| {
-                let mut out = Vec::new();
-                for range in ranges {
-                    out.extend(&lst.map_range(range));
-                }
-                out
+
+    fn part1(almanac: &Almanac) -> anyhow::Result<u64> {
+        almanac
+            .seeds
+            .iter()
+            .map(|&seed| almanac.maps.iter().fold(seed, |n, lst| lst.map(n)))
+            .min()
+            .map(|n| n as u64)
+            .context("no seeds")
+    }
+
+    fn part2(almanac: &Almanac) -> anyhow::Result<u64> {
+        almanac
+            .seeds
+            .iter()
+            .tuples()
+            .map(|(&src, &len)| SeedRange { src, len })
+            .flat_map(|seed_range| {
+                almanac.maps.iter().fold(vec![seed_range], |ranges, lst| {
+                    let mut out = Vec::new();
+                    for range in ranges {
+                        out.extend(&lst.map_range(range));
+                    }
+                    out
+                })
             })
-        })
-        .min_by(|rng1, rng2| rng1.src.cmp(&rng2.src))
-        .unwrap()
-        .src as u64
+            .map(|range| range.src)
+            .min()
+            .map(|n| n as u64)
+            .context("no seeds")
+    }
+}
+
+#[aoc(part = 1, example = 35)]
+pub fn part1(input: &str) -> anyhow::Result<u64> {
+    parse_and_solve::<Day05>(input, Day05::part1)
+}
+
+#[aoc(part = 2, example = 46)]
+pub fn part2(input: &str) -> anyhow::Result<u64> {
+    parse_and_solve::<Day05>(input, Day05::part2)
 }
@@ -2,7 +2,7 @@ use aoc_framework::*;
 
 pub struct Day06;
 
-impl_day!(Day06::{part1, part2}: 2023[6], r"
+impl_day_with_examples!(Day06::{part1, part2}: 2023[6], r"
 Time:      7  15   30
 Distance:  9  40  200
 ");
@@ -37,7 +37,7 @@ fn parse_input(input: &str) -> Vec<Race> {
 }
 
 #[aoc(part = 1, example = 288, benchmark = 1000)]
-fn part1(input: &str) -> u64 {
+pub fn part1(input: &str) -> u64 {
     parse_input(input)
         .into_iter()
         .map(Race::count_ways)
@@ -45,7 +45,7 @@ fn part1(input: &str) -> u64 {
 }
 
 #[aoc(part = 2, example = 71503, benchmark = 1000)]
-fn part2(input: &str) -> u64 {
+pub fn part2(input: &str) -> u64 {
     input
         .lines()
         .map(|ln| {
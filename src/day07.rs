@@ -4,7 +4,7 @@ use aoc_framework::*;
 
 pub struct Day07;
 
-impl_day!(Day07::{part1, part2}: 2023[7], r"
+impl_day_with_examples!(Day07::{part1, part2}: 2023[7], r"
 32T3K 765
 T55J5 684
 KK677 28
@@ -160,11 +160,11 @@ fn solve<const PART2: bool>(input: impl Iterator<Item = String>) -> u64 {
 }
 
 #[aoc(part = 1, example = 6440)]
-fn part1(input: impl Iterator<Item = String>) -> u64 {
+pub fn part1(input: impl Iterator<Item = String>) -> u64 {
     solve::<false>(input)
 }
 
 #[aoc(part = 2, example = 5905)]
-fn part2(input: impl Iterator<Item = String>) -> u64 {
+pub fn part2(input: impl Iterator<Item = String>) -> u64 {
     solve::<true>(input)
 }
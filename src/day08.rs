@@ -1,10 +1,12 @@
 use std::{array, collections::HashMap};
 
-use aoc_framework::*;
+use aoc_framework::{anyhow::Context, *};
+
+use crate::solution::{parse_and_solve, Solution};
 
 pub struct Day08;
 
-impl_day!(Day08::{part1, part2}: 2023[8], r"
+impl_day_with_examples!(Day08::{part1, part2}: 2023[8], r"
 RL
 
 AAA = (BBB, CCC)
@@ -26,6 +28,13 @@ LR
 22C = (22Z, 22Z)
 22Z = (22B, 22B)
 XXX = (XXX, XXX)
+",
+r"
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)
 ");
 
 #[derive(Clone, Copy, Default)]
@@ -52,7 +61,7 @@ fn parse_id(s: &str) -> u32 {
         .fold(0, |acc, n| acc * 36 + n)
 }
 
-fn parse_map(input: impl Iterator<Item = String>) -> Vec<MapEntry> {
+fn parse_map<'a>(input: impl Iterator<Item = &'a str>) -> Vec<MapEntry> {
     let map = input
         .enumerate()
         .flat_map(|(i, ln)| {
@@ -97,18 +106,66 @@ fn count_steps<F: Fn(u32) -> bool>(
     i as u64
 }
 
-#[aoc(part = 1, example = 2)]
-fn part1(mut input: impl Iterator<Item = String>) -> u64 {
-    let mut directions = input.next().unwrap().into_bytes();
-    directions
-        .iter_mut()
-        .for_each(|b| if *b == b'L' { *b = 0 } else { *b = 1 });
-    input.next();
-    let map = parse_map(input);
-    let src_pos = parse_id("AAA");
-    let dst = parse_id("ZZZ");
-    let src_ndx = map.iter().position(|entry| entry.id.id == src_pos).unwrap();
-    count_steps(&map, &directions, src_ndx, |p| p == dst)
+/// Steps from `ndx`, starting at instruction `offset`, until the next node ending in Z, along
+/// with that node.
+fn steps_to_end(
+    map: &[MapEntry],
+    directions: &[u8],
+    mut ndx: usize,
+    offset: usize,
+    limit: usize,
+) -> Option<(usize, usize)> {
+    for i in 1..=limit {
+        let entry = map[ndx].dests[directions[(offset + i - 1) % directions.len()] as usize];
+        ndx = entry.ndx as usize;
+        if entry.id % 36 == 25 {
+            return Some((i, ndx));
+        }
+    }
+    None
+}
+
+/// Part 2 takes the LCM of the steps to each ghost's first Z, which is only right if every
+/// ghost then keeps cycling back to that Z in the same number of steps.
+pub fn check_assumptions(input: &str) -> Vec<(&'static str, bool)> {
+    let mut lines = input.lines();
+    let directions = parse_directions(lines.next().unwrap_or_default());
+    let map = parse_map(lines.skip(1));
+    // past this many steps the ghost is stuck in a loop without any Z
+    let limit = map.len() * directions.len();
+    let cycles = map
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.id.id % 36 == 0)
+        .map(|(src, _)| {
+            let (first, end) = steps_to_end(&map, &directions, src, 0, limit)?;
+            Some((
+                first,
+                end,
+                steps_to_end(&map, &directions, end, first, limit),
+            ))
+        })
+        .collect_vec();
+    vec![
+        (
+            "every ghost reaches a Z node",
+            cycles.iter().all(Option::is_some),
+        ),
+        (
+            "the first Z comes after whole rounds of instructions",
+            cycles
+                .iter()
+                .flatten()
+                .all(|(first, _, _)| first % directions.len() == 0),
+        ),
+        (
+            "each ghost comes back to the same Z in as many steps",
+            cycles
+                .iter()
+                .flatten()
+                .all(|&(first, end, next)| next == Some((first, end))),
+        ),
+    ]
 }
 
 fn gcd(mut a: u64, mut b: u64) -> u64 {
@@ -122,17 +179,60 @@ fn lcm(a: u64, b: u64) -> u64 {
     (a * b) / gcd(a, b)
 }
 
+fn parse_directions(line: &str) -> Vec<u8> {
+    line.bytes().map(|b| (b != b'L') as u8).collect()
+}
+
+pub struct Network {
+    directions: Vec<u8>,
+    map: Vec<MapEntry>,
+}
+
+impl Solution for Day08 {
+    type Parsed = Network;
+    type Output = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Network> {
+        let mut lines = input.lines();
+        let directions = parse_directions(lines.next().context("missing directions")?);
+        let map = parse_map(lines.skip(1));
+        Ok(Network { directions, map })
+    }
+
+    fn part1(network: &Network) -> anyhow::Result<u64> {
+        let src_pos = parse_id("AAA");
+        let dst = parse_id("ZZZ");
+        let src_ndx = network
+            .map
+            .iter()
+            .position(|entry| entry.id.id == src_pos)
+            .context("no AAA node")?;
+        Ok(count_steps(
+            &network.map,
+            &network.directions,
+            src_ndx,
+            |p| p == dst,
+        ))
+    }
+
+    fn part2(network: &Network) -> anyhow::Result<u64> {
+        network
+            .map
+            .iter()
+            .enumerate()
+            .filter(|(_, &p)| p.id.id % 36 == 0)
+            .map(|(src, _)| count_steps(&network.map, &network.directions, src, |p| p % 36 == 25))
+            .reduce(lcm)
+            .context("no nodes ending in A")
+    }
+}
+
+#[aoc(part = 1, example = 2)]
+pub fn part1(input: &str) -> anyhow::Result<u64> {
+    parse_and_solve::<Day08>(input, Day08::part1)
+}
+
 #[aoc(part = 2, example = 6)]
-fn part2(mut input: impl Iterator<Item = String>) -> u64 {
-    let mut directions = input.next().unwrap().into_bytes();
-    directions
-        .iter_mut()
-        .for_each(|b| if *b == b'L' { *b = 0 } else { *b = 1 });
-    input.next();
-    let map = parse_map(input);
-    map.iter()
-        .enumerate()
-        .filter(|(_, &p)| p.id.id % 36 == 0)
-        .map(|(src, _)| count_steps(&map, &directions, src, |p| p % 36 == 25))
-        .fold(0, |acc, n| if acc == 0 { n } else { lcm(acc, n) })
+pub fn part2(input: &str) -> anyhow::Result<u64> {
+    parse_and_solve::<Day08>(input, Day08::part2)
 }
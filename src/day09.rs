@@ -2,7 +2,7 @@ use aoc_framework::*;
 
 pub struct Day09;
 
-impl_day!(Day09::{part1, part2}: 2023[9], r"
+impl_day_with_examples!(Day09::{part1, part2}: 2023[9], r"
 0 3 6 9 12 15
 1 3 6 10 15 21
 10 13 16 21 30 45
@@ -49,17 +49,17 @@ fn extrapolate_front(arr: &mut [i64]) -> i64 {
 }
 
 #[aoc(part = 1, example = 114)]
-fn part1(input: impl Iterator<Item = String>) -> u64 {
+pub fn part1(input: impl Iterator<Item = String>) -> i64 {
     input
         .map(|ln| ln.split(' ').flat_map(|s| s.parse().ok()).collect())
         .map(|mut values: Vec<i64>| extrapolate_back(&mut values))
-        .sum::<i64>() as u64
+        .sum()
 }
 
 #[aoc(part = 2, example = 2)]
-fn part2(input: impl Iterator<Item = String>) -> u64 {
+pub fn part2(input: impl Iterator<Item = String>) -> i64 {
     input
         .map(|ln| ln.split(' ').flat_map(|s| s.parse().ok()).collect())
         .map(|mut values: Vec<i64>| extrapolate_front(&mut values))
-        .sum::<i64>() as u64
+        .sum()
 }
@@ -1,12 +1,10 @@
-use aoc_framework::{
-    grid::{Grid, GridView},
-    point::Point2,
-    *,
-};
+use aoc_framework::{grid::Grid, point::Point2, *};
+
+use crate::geometry::Polygon;
 
 pub struct Day10;
 
-impl_day!(Day10::{part1, part2}: 2023[10], r"
+impl_day_with_examples!(Day10::{part1, part2}: 2023[10], r"
 7-F7-
 .FJ|7
 SJLL7
@@ -32,8 +30,7 @@ const PIPES_DELTAS: [([isize; 2], [u8; 3]); 4] = [
     ([0, -1], [b'7', b'|', b'F']),
 ];
 
-#[aoc(part = 1, example = 8)]
-fn part1(input: Vec<u8>) -> u64 {
+fn find_loop(input: Vec<u8>) -> Vec<Point2> {
     let start_ndx = input.iter().position(|&b| b == b'S').unwrap_or(0);
     let grid = Grid::from_bytes(input);
     let mut pos = grid.offset_to_point(start_ndx);
@@ -49,9 +46,9 @@ fn part1(input: Vec<u8>) -> u64 {
         }
     }
     if delta == Point2::default() {
-        return 0;
+        return Vec::new();
     }
-    let mut len = 1;
+    let mut tiles = vec![pos];
     loop {
         let next = pos + delta;
         delta = match (grid.get(next).unwrap(), delta.x(), delta.y()) {
@@ -64,83 +61,43 @@ fn part1(input: Vec<u8>) -> u64 {
             _ => unreachable!(),
         }
         .into();
-        len += 1;
+        tiles.push(next);
         pos = next;
     }
-    len / 2
+    tiles
 }
 
-fn flood_fill(grid: &mut GridView<'_, u8, 2>) {
-    let start = Point2::default();
-    grid.set(start, b'.');
-    let mut stack = vec![Point2::new(0, 0)];
-    while let Some(pos) = stack.pop() {
-        const DELTAS: [Point2; 4] = [
-            Point2::new(1, 0),
-            Point2::new(0, 1),
-            Point2::new(-1, 0),
-            Point2::new(0, -1),
-        ];
-        for d in DELTAS {
-            let neigh = pos + d;
-            if let Some(b' ') = grid.get(neigh) {
-                grid.set(neigh, b'.');
-                stack.push(neigh);
-            }
-        }
-    }
+/// Following the loop relies on S connecting to exactly two of its neighbours.
+pub fn check_assumptions(input: &str) -> Vec<(&'static str, bool)> {
+    let grid = Grid::from_bytes(input.as_bytes().to_vec());
+    let start = input.bytes().position(|b| b == b'S');
+    let connections = start.map_or(0, |ndx| {
+        let pos = grid.offset_to_point(ndx);
+        PIPES_DELTAS
+            .iter()
+            .filter(|(d, pipes)| {
+                grid.get(pos + Point2::from(*d))
+                    .is_some_and(|b| pipes.contains(b))
+            })
+            .count()
+    });
+    vec![
+        ("there is an S", start.is_some()),
+        ("S connects to exactly two pipes", connections == 2),
+    ]
+}
+
+#[aoc(part = 1, example = 8)]
+pub fn part1(input: Vec<u8>) -> usize {
+    find_loop(input).len() / 2
 }
 
 #[aoc(part = 2, example = 10)]
-fn part2(input: Vec<u8>) -> u64 {
-    let start_ndx = input.iter().position(|&b| b == b'S').unwrap_or(0);
-    let grid = Grid::from_bytes(input);
-    let size = grid.size();
-    let mut fill_grid = Grid::from_data(
-        vec![b' '; (size.x() * size.y()) as usize * 9],
-        size.x() as usize * 3,
-    );
-    let mut pos = grid.offset_to_point(start_ndx);
-    let mut delta = Point2::default();
-    for (d, pipes) in PIPES_DELTAS {
-        let neighbor = pos + Point2::from(d);
-        let Some(&b) = grid.get(neighbor) else {
-            continue;
-        };
-        if pipes.contains(&b) {
-            delta = d.into();
-            break;
-        }
-    }
-    if delta == Point2::default() {
+pub fn part2(input: Vec<u8>) -> u64 {
+    let tiles = find_loop(input);
+    if tiles.is_empty() {
         return 0;
     }
-    loop {
-        let next = pos + delta;
-        let new_delta = match (grid.get(next).unwrap(), delta.x(), delta.y()) {
-            (b'S', _, _) => break,
-            (b'-' | b'|', dx, dy) => [dx, dy],
-            (b'F', 0, -1) | (b'L', 0, 1) => [1, 0],
-            (b'7', 1, 0) | (b'F', -1, 0) => [0, 1],
-            (b'7', 0, -1) | (b'J', 0, 1) => [-1, 0],
-            (b'J', 1, 0) | (b'L', -1, 0) => [0, -1],
-            _ => unreachable!(),
-        }
-        .into();
-        for i in 1..=3 {
-            let fill_pos = pos * 3 + delta * i + Point2::new(1, 1);
-            fill_grid.set(fill_pos, b'#');
-        }
-        for i in 1..=3 {
-            let fill_pos = next * 3 + new_delta * i + Point2::new(1, 1);
-            fill_grid.set(fill_pos, b'#');
-        }
-        pos = next;
-        delta = new_delta;
-    }
-    flood_fill(&mut fill_grid);
-    grid.points_iter()
-        .flat_map(|p| fill_grid.get(p * 3 + Point2::new(1, 1)))
-        .filter(|&&b| b == b' ')
-        .count() as u64
+    // every tile of the loop is a vertex, so the loop tiles are exactly the boundary points
+    Polygon::from_vertices(tiles).interior_points()
 }
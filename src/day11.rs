@@ -2,7 +2,7 @@ use aoc_framework::{grid::Grid, point::Point, *};
 
 pub struct Day11;
 
-impl_day!(Day11::{part1, part2}: 2023[11], r"
+impl_day_with_examples!(Day11::{part1, part2}: 2023[11], r"
 ...#......
 .......#..
 #.........
@@ -22,7 +22,9 @@ fn to_cumulative(arr: &mut [isize]) {
     });
 }
 
-fn solve(input: Vec<u8>, added_dist: isize) -> u64 {
+/// Sum of the distances between every pair of galaxies, with empty rows and columns
+/// `added_dist` times as wide.
+pub fn sum_distances(input: Vec<u8>, added_dist: isize) -> usize {
     let grid = Grid::from_bytes(input);
     let mut galaxies = Vec::new();
     let size = grid.size();
@@ -49,15 +51,15 @@ fn solve(input: Vec<u8>, added_dist: isize) -> u64 {
         })
         .tuple_combinations()
         .map(|(p1, p2)| p1.dist_manhattan(p2))
-        .sum::<usize>() as u64
+        .sum()
 }
 
 #[aoc(part = 1, example = 374)]
-fn part1(input: Vec<u8>) -> u64 {
-    solve(input, 2)
+pub fn part1(input: Vec<u8>) -> usize {
+    sum_distances(input, 2)
 }
 
 #[aoc(part = 2, example = 82000210)]
-fn part2(input: Vec<u8>) -> u64 {
-    solve(input, 1000000)
+pub fn part2(input: Vec<u8>) -> usize {
+    sum_distances(input, 1000000)
 }
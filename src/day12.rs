@@ -1,10 +1,12 @@
 use std::{collections::HashMap, iter};
 
-use aoc_framework::*;
+use aoc_framework::{anyhow::Context, *};
+
+use crate::solution::{parse_and_solve, Solution};
 
 pub struct Day12;
 
-impl_day!(Day12::{part1, part2}: 2023[12], r"
+impl_day_with_examples!(Day12::{part1, part2}: 2023[12], r"
 ???.### 1,1,3
 .??..??...?##. 1,1,3
 ?#?#?#?#?#?#?#? 1,3,1,6
@@ -68,36 +70,69 @@ fn solve_part<'a>(
     res
 }
 
-#[aoc(part = 1, example = 21)]
-fn part1(input: impl Iterator<Item = String>) -> u64 {
-    input
-        .flat_map(|ln| {
-            let mut memo = HashMap::new();
-            let (row, rle) = ln.split_once(' ')?;
-            let rle = rle
-                .split(',')
-                .map(|s| s.parse::<usize>().unwrap())
-                .collect_vec();
-            let count = solve_part(row.as_bytes(), &rle, &mut memo);
-            count
+pub struct Row {
+    springs: Vec<u8>,
+    runs: Vec<usize>,
+}
+
+impl Row {
+    fn parse(line: &str) -> anyhow::Result<Self> {
+        let (springs, runs) = line
+            .split_once(' ')
+            .with_context(|| format!("expected springs and runs, got {line:?}"))?;
+        let runs = runs
+            .split(',')
+            .map(|s| s.parse::<usize>())
+            .collect::<Result<_, _>>()
+            .with_context(|| format!("bad runs in {line:?}"))?;
+        Ok(Row {
+            springs: springs.as_bytes().to_vec(),
+            runs,
         })
-        .sum()
+    }
+
+    fn arrangements(&self) -> u64 {
+        let mut memo = HashMap::new();
+        solve_part(&self.springs, &self.runs, &mut memo).unwrap_or(0)
+    }
+
+    fn unfold(&self) -> Row {
+        Row {
+            springs: iter::repeat_n(&self.springs[..], 5)
+                .collect_vec()
+                .join(&b'?'),
+            runs: self.runs.repeat(5),
+        }
+    }
+}
+
+impl Solution for Day12 {
+    type Parsed = Vec<Row>;
+    type Output = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Vec<Row>> {
+        input
+            .lines()
+            .filter(|ln| !ln.is_empty())
+            .map(Row::parse)
+            .collect()
+    }
+
+    fn part1(rows: &Vec<Row>) -> anyhow::Result<u64> {
+        Ok(rows.iter().map(Row::arrangements).sum())
+    }
+
+    fn part2(rows: &Vec<Row>) -> anyhow::Result<u64> {
+        Ok(rows.iter().map(|row| row.unfold().arrangements()).sum())
+    }
+}
+
+#[aoc(part = 1, example = 21)]
+pub fn part1(input: &str) -> anyhow::Result<u64> {
+    parse_and_solve::<Day12>(input, Day12::part1)
 }
 
 #[aoc(part = 2, example = 525152)]
-fn part2(input: impl Iterator<Item = String>) -> u64 {
-    input
-        .flat_map(|ln| {
-            let (row, rle) = ln.split_once(' ')?;
-            let row = iter::repeat(row).take(5).join("?");
-            let rle = iter::repeat(rle)
-                .take(5)
-                .join(",")
-                .split(',')
-                .map(|s| s.parse::<usize>().unwrap())
-                .collect_vec();
-            let mut memo = HashMap::new();
-            solve_part(row.as_bytes(), &rle, &mut memo)
-        })
-        .sum()
+pub fn part2(input: &str) -> anyhow::Result<u64> {
+    parse_and_solve::<Day12>(input, Day12::part2)
 }
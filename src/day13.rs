@@ -6,7 +6,7 @@ use aoc_framework::{
 
 pub struct Day13;
 
-impl_day!(Day13::{part1, part2}: 2023[13], r"
+impl_day_with_examples!(Day13::{part1, part2}: 2023[13], r"
 #.##..##.
 ..#.##.#.
 ##......#
@@ -90,11 +90,11 @@ fn solve(mut input: impl Iterator<Item = String>, f: impl Fn(&GridView) -> u64)
 }
 
 #[aoc(part = 1, example = 405)]
-fn part1(input: impl Iterator<Item = String>) -> u64 {
+pub fn part1(input: impl Iterator<Item = String>) -> u64 {
     solve(input, find_reflection)
 }
 
 #[aoc(part = 2, example = 400)]
-fn part2(input: impl Iterator<Item = String>) -> u64 {
+pub fn part2(input: impl Iterator<Item = String>) -> u64 {
     solve(input, find_reflection_smudged)
 }
@@ -7,7 +7,7 @@ use aoc_framework::{
 
 pub struct Day14;
 
-impl_day!(Day14::{part1, part2}: 2023[14], r"
+impl_day_with_examples!(Day14::{part1, part2}: 2023[14], r"
 O....#....
 O.OO#....#
 .....##...
@@ -21,7 +21,7 @@ O.#..O.#.#
 ");
 
 #[aoc(part = 1, example = 136)]
-fn part1(input: Vec<u8>) -> u64 {
+pub fn part1(input: Vec<u8>) -> u64 {
     let g = Grid::from_bytes(input);
     let Point([w, h]) = g.size();
     let mut load = 0;
@@ -115,7 +115,7 @@ fn find_cycle(values: &[u64]) -> Option<usize> {
 }
 
 #[aoc(part = 2, example = 64)]
-fn part2(input: Vec<u8>) -> u64 {
+pub fn part2(input: Vec<u8>) -> u64 {
     let mut grid = Grid::from_bytes(input);
     let mut values = Vec::new();
     let mut i = 0;
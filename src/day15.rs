@@ -6,7 +6,7 @@ use aoc_framework::*;
 
 pub struct Day15;
 
-impl_day!(Day15::{part1, part2}: 2023[15], r"
+impl_day_with_examples!(Day15::{part1, part2}: 2023[15], r"
 rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7
 ");
 
@@ -15,12 +15,12 @@ fn hash(s: &str) -> u64 {
 }
 
 #[aoc(part = 1, example = 1320)]
-fn part1(input: impl Iterator<Item = String>) -> u64 {
+pub fn part1(input: impl Iterator<Item = String>) -> u64 {
     input.map(|ln| ln.split(',').map(hash).sum::<u64>()).sum()
 }
 
 #[aoc(part = 2, example = 145, benchmark = 1000)]
-fn part2(input: &str) -> u64 {
+pub fn part2(input: &str) -> u64 {
     let mut map: [SmallVec<[u64; 6]>; 256] = array::from_fn(|_| SmallVec::new());
     input.lines().for_each(|ln| {
         ln.as_bytes().split(|&b| b == b',').for_each(|step| {
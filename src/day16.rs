@@ -7,7 +7,7 @@ use aoc_framework::{
 
 pub struct Day16;
 
-impl_day!(Day16::{part1, part2}: 2023[16], r"
+impl_day_with_examples!(Day16::{part1, part2}: 2023[16], r"
 .|...\....
 |.-.\.....
 .....|-...
@@ -57,13 +57,13 @@ fn count_energized(g: &GridView<'_, u8, 2>, start_pos: Point<2>, start_dir: Dire
 }
 
 #[aoc(part = 1, example = 46)]
-fn part1(input: Vec<u8>) -> u64 {
+pub fn part1(input: Vec<u8>) -> u64 {
     let g = Grid::from_bytes(input);
     count_energized(&g, Point([0, 0]), Direction::EAST)
 }
 
 #[aoc(part = 2, example = 51)]
-fn part2(input: Vec<u8>) -> u64 {
+pub fn part2(input: Vec<u8>) -> u64 {
     let g = Grid::from_bytes(input);
     let Point([w, h]) = g.size();
     let mut max = 0;
@@ -1,19 +1,18 @@
-use std::{
-    cmp::Reverse,
-    collections::{BinaryHeap, HashMap},
-    hash::Hash,
-};
+use std::fmt::Write;
 
 use aoc_framework::{
+    anyhow::Context,
     direction::Direction,
     grid::{Grid, GridView},
     point::{Point, Point2},
     *,
 };
 
+use crate::pathfinding::{a_star, Dense, Path};
+
 pub struct Day17;
 
-impl_day!(Day17::{part1, part2}: 2023[17], r"
+impl_day_with_examples!(Day17::{part1, part2}: 2023[17], r"
 2413432311323
 3215453535623
 3255245654254
@@ -38,127 +37,182 @@ impl_day!(Day17::{part1, part2}: 2023[17], r"
 #[derive(PartialEq, Eq, Clone, Copy)]
 struct Node {
     p: Point2,
-    dir: Direction<2>,
-    count: u8,
-}
-
-impl Hash for Node {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.p.0.hash(state);
-        self.dir.delta().0.hash(state);
-        self.count.hash(state);
-    }
+    // whether the run that led to this node was vertical, the next one has to turn
+    vertical: bool,
 }
 
-struct KeyedBy<T, O: Ord> {
-    v: T,
-    o: O,
+fn heat_loss(g: &GridView<'_, u8, 2>, p: Point2) -> u64 {
+    (g[p] - b'0') as u64
 }
 
-impl<T, O: Ord> PartialEq for KeyedBy<T, O> {
-    fn eq(&self, other: &Self) -> bool {
-        self.o == other.o
-    }
+fn crucible_moves<'a>(
+    g: &'a GridView<'a, u8, 2>,
+    node: &Node,
+    min_run: isize,
+    max_run: isize,
+) -> impl Iterator<Item = (Node, u64)> + 'a {
+    let turns = if node.vertical {
+        [Direction::EAST, Direction::WEST]
+    } else {
+        [Direction::NORTH, Direction::SOUTH]
+    };
+    let Node { p: start, vertical } = *node;
+    turns.into_iter().flat_map(move |dir| {
+        (1..=max_run)
+            .map(move |n| start + dir.delta() * n)
+            .take_while(|&p| g.in_bounds(p))
+            .scan(0, |cost, p| {
+                *cost += heat_loss(g, p);
+                Some((p, *cost))
+            })
+            // every move goes at least one block, whatever the minimum run
+            .skip(min_run.max(1) as usize - 1)
+            .map(move |(p, cost)| {
+                let vertical = !vertical;
+                (Node { p, vertical }, cost)
+            })
+    })
 }
 
-impl<T, O: Ord> Eq for KeyedBy<T, O> {}
-
-impl<T, O: Ord> PartialOrd for KeyedBy<T, O> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
+fn find_path(g: &GridView<'_, u8, 2>, min_run: isize, max_run: isize) -> Option<Path<Node>> {
+    let Point([w, h]) = g.size();
+    let end_pos = Point([w - 1, h - 1]);
+    let mut visited = Dense::new((w * h) as usize * 2, |node: &Node| {
+        (node.p.y() * w + node.p.x()) as usize * 2 + node.vertical as usize
+    });
+    let starts = [false, true].map(|vertical| Node {
+        p: Point([0, 0]),
+        vertical,
+    });
+    // each remaining block costs at least the cheapest one, which keeps the distance admissible
+    // even when some blocks lose no heat
+    let min_loss = g.points_iter().map(|p| heat_loss(g, p)).min().unwrap_or(0);
+    a_star(
+        starts,
+        &mut visited,
+        |node| crucible_moves(g, node, min_run, max_run),
+        |node| node.p.dist_manhattan(end_pos) as u64 * min_loss,
+        |node| node.p == end_pos,
+    )
 }
 
-impl<T, O: Ord> Ord for KeyedBy<T, O> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.o.cmp(&other.o)
+fn arrow(step: Point2) -> char {
+    match step.0 {
+        [1, 0] => '>',
+        [-1, 0] => '<',
+        [0, 1] => 'v',
+        _ => '^',
     }
 }
 
-fn total_cost(g: &GridView<'_, u8, 2>, last: &Node, came_from: &HashMap<Node, Node>) -> u64 {
-    let mut total = (g[last.p] - b'0') as u64;
-    let mut cur = *last;
-    while let Some(node) = came_from.get(&cur) {
-        if node.p.0 == [0, 0] {
-            break;
+fn render_path(g: &GridView<'_, u8, 2>, path: &Path<Node>) -> String {
+    let mut overlay = g.clone().to_owned();
+    let mut segments = String::new();
+    let mut total = 0;
+    for (from, to) in path.states.iter().tuple_windows() {
+        let delta = to.p - from.p;
+        let run = delta.dist_manhattan(Point::default()) as isize;
+        let step = Point([delta.x().signum(), delta.y().signum()]);
+        let mut cost = 0;
+        for n in 1..=run {
+            let p = from.p + step * n;
+            cost += heat_loss(g, p);
+            overlay.set(p, arrow(step) as u8);
         }
-        total += (g[node.p] - b'0') as u64;
-        cur = *node;
+        total += cost;
+        let _ = writeln!(
+            segments,
+            "  {} x{run:<2} from {:?}: +{cost:<3} = {total}",
+            arrow(step),
+            from.p.0
+        );
     }
-    return total;
+    let mut out = String::new();
+    let Point([w, h]) = g.size();
+    for y in 0..h {
+        let row = (0..w).map(|x| overlay[(x, y)] as char).collect::<String>();
+        let _ = writeln!(out, "{row}");
+    }
+    out.push_str(&segments);
+    let _ = writeln!(out, "total heat loss: {}", path.cost);
+    out
 }
 
-fn find_path(
-    input: Vec<u8>,
-    valid: impl Fn(&Node, isize) -> bool,
-    end_is_valid: impl Fn(&Node) -> bool,
-) -> u64 {
+pub fn render(input: Vec<u8>) -> String {
     let g = Grid::from_bytes(input);
-    let end_pos = g.size() - Point([1, 1]);
-    let mut open_set = BinaryHeap::new();
-    let start_node = Node {
-        p: Point([0, 0]),
-        dir: Direction::EAST,
-        count: 0,
-    };
-    open_set.push(KeyedBy {
-        v: start_node,
-        o: Reverse(0),
-    });
-    let mut came_from = HashMap::<Node, Node>::new();
-    let mut g_score = HashMap::new();
-    g_score.insert(start_node, 0);
-
-    while let Some(KeyedBy { v: current, .. }) = open_set.pop() {
-        if current.p == end_pos && end_is_valid(&current) {
-            return total_cost(&g, &current, &came_from);
-        }
-        for d in 0..3 {
-            if !valid(&current, d) {
-                continue;
-            }
-            let dir = current.dir - 1 + d;
-            let neighbor = current.p + dir;
-            if !g.in_bounds(neighbor) {
-                continue;
-            }
-            let neighbor_node = Node {
-                p: neighbor,
-                dir,
-                count: if dir == current.dir {
-                    current.count + 1
-                } else {
-                    1
-                },
-            };
-            let tentative_score =
-                g_score.get(&current).unwrap_or(&1000000) + (g[neighbor] - b'0') as u64;
-            if &tentative_score < g_score.get(&neighbor_node).unwrap_or(&1000000) {
-                came_from.insert(neighbor_node, current);
-                g_score.insert(neighbor_node, tentative_score);
-                let f = tentative_score * 100 + neighbor.dist_manhattan(end_pos) as u64;
-                open_set.push(KeyedBy {
-                    v: neighbor_node,
-                    o: Reverse(f),
-                });
-            }
+    let mut out = String::new();
+    for (part, min_run, max_run) in [(1, 1, 3), (2, 4, 10)] {
+        let _ = writeln!(out, "Part {part} (runs of {min_run} to {max_run}):");
+        match find_path(&g, min_run, max_run) {
+            Some(path) => out.push_str(&render_path(&g, &path)),
+            None => out.push_str("no path\n"),
         }
     }
-    0
+    out
 }
 
 #[aoc(part = 1, example = 102)]
-fn part1(input: Vec<u8>) -> u64 {
-    find_path(input, |current, d| d != 1 || current.count < 3, |_| true)
+pub fn part1(input: Vec<u8>) -> anyhow::Result<u64> {
+    let g = Grid::from_bytes(input);
+    find_path(&g, 1, 3)
+        .map(|path| path.cost)
+        .context("no path to the bottom-right block")
 }
 
 #[aoc(part = 2, example = 71)]
-fn part2(input: Vec<u8>) -> u64 {
-    find_path(
-        input,
-        |current, d| {
-            current.count == 0 || (d == 1 && current.count < 10) || (d != 1 && current.count >= 4)
-        },
-        |end| end.count >= 4,
-    )
+pub fn part2(input: Vec<u8>) -> anyhow::Result<u64> {
+    let g = Grid::from_bytes(input);
+    find_path(&g, 4, 10)
+        .map(|path| path.cost)
+        .context("no path to the bottom-right block")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cost(input: &str, min_run: isize, max_run: isize) -> Option<u64> {
+        let g = Grid::from_bytes(input.as_bytes().to_vec());
+        find_path(&g, min_run, max_run).map(|path| path.cost)
+    }
+
+    #[test]
+    fn run_limits() {
+        // a single row leaves no room to turn, so the whole row has to be one run
+        assert_eq!(cost("11111\n", 1, 3), None);
+        assert_eq!(cost("11111\n", 1, 4), Some(4));
+        assert_eq!(cost("11111\n", 4, 4), Some(4));
+        assert_eq!(cost("11111\n", 5, 10), None);
+        assert_eq!(cost("11111\n", 0, 4), Some(4));
+    }
+
+    #[test]
+    fn free_blocks() {
+        // the heuristic must not assume every block loses heat
+        assert_eq!(cost("100\n990\n", 1, 3), Some(0));
+        assert_eq!(cost("1009\n9909\n9900\n", 1, 3), Some(0));
+    }
+
+    #[test]
+    fn render_overlay_and_segments() {
+        assert_eq!(
+            render(b"1111\n9991\n".to_vec()),
+            concat!(
+                "Part 1 (runs of 1 to 3):\n",
+                "1>>>\n",
+                "999v\n",
+                "  > x3  from [0, 0]: +3   = 3\n",
+                "  v x1  from [3, 0]: +1   = 4\n",
+                "total heat loss: 4\n",
+                "Part 2 (runs of 4 to 10):\n",
+                "no path\n",
+            )
+        );
+    }
+
+    #[test]
+    fn unreachable_goal_is_an_error() {
+        assert!(part1(b"11111\n".to_vec()).is_err());
+        assert_eq!(part1(b"11111\n11111\n".to_vec()).unwrap(), 5);
+    }
 }
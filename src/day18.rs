@@ -1,13 +1,15 @@
 use aoc_framework::{
     direction::Direction,
-    grid::{Grid, GridView},
-    point::{Point},
+    grid::Grid,
+    point::{Point, Point2},
     *,
 };
 
+use crate::geometry::Polygon;
+
 pub struct Day18;
 
-impl_day!(Day18::{part1, part2}: 2023[18], r"
+impl_day_with_examples!(Day18::{part1, part2}: 2023[18], r"
 R 6 (#70c710)
 D 5 (#0dc571)
 L 2 (#5713f0)
@@ -24,97 +26,102 @@ L 2 (#015232)
 U 2 (#7a21e3)
 ");
 
-fn flood_fill(g: &mut GridView<'_, u8, 2>) -> isize {
-    let mut stack = vec![Point([0, 0])];
-    g.set(Point([0, 0]), b' ');
-    let mut count = 1;
-    while let Some(p) = stack.pop() {
-        for i in 0..4 {
-            let neighbor = p + Direction::new(i);
-            let Some(b'.') = g.get(neighbor) else {
-                continue;
-            };
-            g.set(neighbor, b' ');
-            stack.push(neighbor);
-            count += 1;
-        }
-    }
-    count
+fn parse_segment(ln: &str) -> Option<(Direction<2>, isize)> {
+    let (dir, rem) = ln.split_once(' ')?;
+    let (len, _) = rem.split_once(' ')?;
+    let dir = match dir {
+        "R" => Direction::EAST,
+        "D" => Direction::SOUTH,
+        "L" => Direction::WEST,
+        _ => Direction::NORTH,
+    };
+    Some((dir, len.parse().ok()?))
 }
 
-#[aoc(part = 1, example = 62)]
-fn part1(input: impl Iterator<Item = String>) -> u64 {
-    let mut current = Point([0, 0]);
-    let mut min = current;
-    let mut max = current;
+fn parse_hex_segment(ln: &str) -> Option<(Direction<2>, isize)> {
+    let (_, hex_code) = ln.split_once('#')?;
+    let len = isize::from_str_radix(&hex_code[..5], 16).ok()?;
+    let dir = match hex_code.as_bytes()[5] {
+        b'0' => Direction::EAST,
+        b'1' => Direction::SOUTH,
+        b'2' => Direction::WEST,
+        _ => Direction::NORTH,
+    };
+    Some((dir, len))
+}
+
+fn parse_colour(ln: &str) -> Option<[u8; 3]> {
+    let (_, hex_code) = ln.split_once('#')?;
+    let rgb = u32::from_str_radix(hex_code.get(..6)?, 16).ok()?;
+    let [_, r, g, b] = rgb.to_be_bytes();
+    Some([r, g, b])
+}
+
+fn bounds(points: impl Iterator<Item = Point2>) -> (Point2, Point2) {
+    points.fold((Point::default(), Point::default()), |(min, max), p| {
+        (
+            Point([min.x().min(p.x()), min.y().min(p.y())]),
+            Point([max.x().max(p.x()), max.y().max(p.y())]),
+        )
+    })
+}
+
+/// Part 1 outline as a binary PPM image, each trench drawn in its own colour.
+pub fn export_ppm(input: &str) -> Vec<u8> {
     let segments = input
-        .flat_map(|ln| {
-            let (dir, rem) = ln.split_once(' ')?;
-            let (len, _) = rem.split_once(' ')?;
-            let dir = match dir {
-                "R" => Direction::EAST,
-                "D" => Direction::SOUTH,
-                "L" => Direction::WEST,
-                _ => Direction::NORTH,
-            };
-            let len = len.parse::<isize>().ok()?;
-            current += dir.delta() * (len);
-            if current.x() < min.x() {
-                min.0[0] = current.0[0];
-            }
-            if current.y() < min.y() {
-                min.0[1] = current.0[1];
-            }
-            if current.x() > max.x() {
-                max.0[0] = current.0[0];
-            }
-            if current.y() > max.y() {
-                max.0[1] = current.0[1];
-            }
-            Some((dir, len))
-        })
+        .lines()
+        .flat_map(|ln| Some((parse_segment(ln)?, parse_colour(ln)?)))
         .collect_vec();
-    let Point([w, h]) = max - min;
-    let (w, h) = (w + 3, h + 3);
-    let mut g = Grid::from_data(vec![b'.'; (h * (w)) as usize], w as usize);
+    let polygon = Polygon::from_segments(segments.iter().map(|&(segment, _)| segment));
+    let (min, max) = bounds(polygon.vertices().iter().copied());
+    let Point([w, h]) = max - min + Point([1, 1]);
+    let mut image = Grid::from_data(vec![[0u8; 3]; (w * h) as usize], w as usize);
     segments
         .into_iter()
-        .fold(-min + Point([1, 1]), |cur, (dir, len)| {
+        .fold(-min, |cur, ((dir, len), colour)| {
             for n in 1..=len {
-                g.set(cur + dir.delta() * n, b'#');
+                image.set(cur + dir.delta() * n, colour);
             }
             cur + dir.delta() * len
         });
-    let outside = flood_fill(&mut g);
-    ((w) * h - outside) as u64
+    let mut out = format!("P6\n{w} {h}\n255\n").into_bytes();
+    out.extend(image.data().iter().flatten());
+    out
 }
 
-#[aoc(part = 2, example = 952408144115)]
-fn part2(input: impl Iterator<Item = String>) -> u64 {
-    let start = Point::default();
-    let mut current = start;
-    let mut perimeter = 0;
-    let points = input
-        .flat_map(|ln| {
-            let (_, hex_code) = ln.split_once('#')?;
-            let len = isize::from_str_radix(&hex_code[..5], 16).ok()?;
-            let dir = Direction::<2>::new(hex_code.as_bytes()[5] - b'0');
-            perimeter += len;
-            Some((dir, len))
-        })
-        .map(|(dir, len)| {
-            current += dir.delta() * len;
-            current
-        })
-        .collect_vec();
-    points
+/// Part 2 outline as an SVG, scaled down to fit in a 1000px square.
+pub fn export_svg(input: &str) -> String {
+    const SIZE: f64 = 1000.;
+    let polygon = Polygon::from_segments(input.lines().flat_map(parse_hex_segment));
+    let (min, max) = bounds(polygon.vertices().iter().copied());
+    let Point([w, h]) = max - min;
+    let scale = SIZE / w.max(h).max(1) as f64;
+    let points = polygon
+        .vertices()
         .iter()
-        .chain(points.first())
-        .tuple_windows()
-        .map(|(l, r)| l.x() * r.y() - r.x() * l.y())
-        .sum::<isize>()
-        .abs() as u64
-        / 2
-        + (perimeter / 2) as u64
-        + 1
+        .map(|&p| {
+            let Point([x, y]) = p - min;
+            format!("{:.1},{:.1}", x as f64 * scale, y as f64 * scale)
+        })
+        .join(" ");
+    format!(
+        concat!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-5 -5 {w} {h}\">\n",
+            "  <polygon points=\"{points}\" fill=\"#d0d0d0\" stroke=\"black\"/>\n",
+            "</svg>\n",
+        ),
+        w = w as f64 * scale + 10.,
+        h = h as f64 * scale + 10.,
+        points = points,
+    )
+}
+
+#[aoc(part = 1, example = 62)]
+pub fn part1(input: impl Iterator<Item = String>) -> u64 {
+    Polygon::from_segments(input.flat_map(|ln| parse_segment(&ln))).enclosed_points()
+}
+
+#[aoc(part = 2, example = 952408144115)]
+pub fn part2(input: impl Iterator<Item = String>) -> u64 {
+    Polygon::from_segments(input.flat_map(|ln| parse_hex_segment(&ln))).enclosed_points()
 }
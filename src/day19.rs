@@ -1,11 +1,13 @@
-use std::{array, collections::{HashMap, HashSet}, iter, ops::Range};
+use std::{array, collections::HashMap, fmt::Write, iter, ops::Range};
 
-use aoc_framework::*;
+use aoc_framework::{anyhow::Context, *};
 use smallvec::SmallVec;
 
+use crate::solution::{parse_and_solve, Solution};
+
 pub struct Day19;
 
-impl_day!(Day19::{part1, part2}: 2023[19], r"
+impl_day_with_examples!(Day19::{part1, part2}: 2023[19], r"
 px{a<2006:qkq,m>2090:A,rfg}
 pv{a>1716:R,A}
 lnx{m>1548:A,A}
@@ -28,17 +30,30 @@ hdj{m>838:A,pv}
 #[derive(Debug, Clone, Copy)]
 struct Record([u16; 4]);
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Target {
+    Accept,
+    Reject,
+    Workflow(u32),
+}
+
 #[derive(Clone, Copy)]
 struct Cond {
     elem: u8,
     gt: bool,
     value: u16,
-    tgt: u32,
+    tgt: Target,
 }
 
 struct Node {
     conds: SmallVec<[Cond; 4]>,
-    default: u32,
+    default: Target,
+}
+
+struct Workflows {
+    names: Vec<String>,
+    nodes: Vec<Node>,
+    start: u32,
 }
 
 impl Cond {
@@ -53,7 +68,7 @@ impl Cond {
 }
 
 impl Node {
-    fn output(&self, rec: Record) -> u32 {
+    fn output(&self, rec: Record) -> Target {
         self.conds
             .iter()
             .find(|cond| cond.matches(rec))
@@ -62,166 +77,610 @@ impl Node {
     }
 }
 
-fn parse_label(s: &str) -> u32 {
-    s.bytes().fold(0, |acc, b| acc << 8 | (b as u32))
+const ELEMS: [&str; 4] = ["x", "m", "a", "s"];
+
+fn elem_to_offset(elem: &str) -> Option<u8> {
+    ELEMS.iter().position(|&e| e == elem).map(|ndx| ndx as u8)
 }
 
-fn elem_to_offset(b: u8) -> u8 {
-    match b {
-        b'x' => 0,
-        b'm' => 1,
-        b'a' => 2,
-        _ => 3,
+fn label_to_target(label: &str, label_map: &mut HashMap<String, u32>) -> Target {
+    match label {
+        "A" => Target::Accept,
+        "R" => Target::Reject,
+        _ => {
+            let id = label_map.len() as u32;
+            Target::Workflow(*label_map.entry(label.to_string()).or_insert(id))
+        }
     }
 }
 
-fn parse_cond(cond: &str) -> Option<Cond> {
-    let (cond, tgt) = cond.split_once(':')?;
-    let elem = elem_to_offset(cond.as_bytes()[0]);
-    let gt = cond.as_bytes()[1] == b'>';
-    let value = cond[2..].parse().ok()?;
-    Some(Cond {
+fn parse_cond(cond: &str, label_map: &mut HashMap<String, u32>) -> anyhow::Result<Cond> {
+    let parsed = cond.split_once(':').and_then(|(check, tgt)| {
+        let split = check.find(['<', '>'])?;
+        let elem = elem_to_offset(&check[..split])?;
+        let value = check[split + 1..].parse().ok()?;
+        Some((elem, &check[split..=split] == ">", value, tgt))
+    });
+    let (elem, gt, value, tgt) =
+        parsed.with_context(|| format!("expected e.g. x>10:A, got {cond:?}"))?;
+    Ok(Cond {
         elem,
         gt,
         value,
-        tgt: parse_label(tgt),
+        tgt: label_to_target(tgt, label_map),
     })
 }
 
-fn parse_rec(rec: &str) -> Record {
+fn parse_rec(rec: &str) -> anyhow::Result<Record> {
     let mut arr = [0; 4];
-    rec[1..rec.len() - 1]
-        .split(',')
-        .map(|elem| elem[2..].parse::<u16>().unwrap())
-        .enumerate()
-        .for_each(|(i, n)| arr[i] = n);
-    Record(arr)
-}
-
-fn parse_nodes(input: impl Iterator<Item = String>) -> HashMap<u32, Node> {
-    input
-        .take_while(|ln| !ln.is_empty())
-        .flat_map(|ln| {
-            let pos = ln.find('{')?;
-            let label = parse_label(&ln[..pos]);
-            let (conds, default) = ln[pos + 1..ln.len() - 1].rsplit_once(',')?;
-            let conds = conds.split(',').flat_map(parse_cond).collect();
-            let node = Node {
-                conds,
-                default: parse_label(default),
-            };
-            Some((label, node))
-        })
-        .collect()
+    let ratings = rec
+        .strip_prefix('{')
+        .and_then(|rec| rec.strip_suffix('}'))
+        .with_context(|| format!("expected a part in braces, got {rec:?}"))?;
+    for (i, elem) in ratings.split(',').enumerate().take(4) {
+        arr[i] = elem
+            .get(2..)
+            .unwrap_or_default()
+            .parse()
+            .with_context(|| format!("invalid rating {elem:?}"))?;
+    }
+    Ok(Record(arr))
 }
 
-#[aoc(part = 1, example = 19114)]
-fn part1(mut input: impl Iterator<Item = String>) -> u64 {
-    let nodes = parse_nodes(&mut input);
-    let a = parse_label("A");
-    let r = parse_label("R");
-    let start = parse_label("in");
-    let mut sum = 0;
-    input.map(|ln| parse_rec(&ln)).for_each(|rec| {
-        let mut current = start;
-        while current != a && current != r {
-            current = nodes[&current].output(rec);
+fn parse_workflows<'a>(input: impl Iterator<Item = &'a str>) -> anyhow::Result<Workflows> {
+    let mut label_map = HashMap::new();
+    let mut nodes: Vec<Option<Node>> = Vec::new();
+    for ln in input.take_while(|ln| !ln.is_empty()) {
+        let (label, rules) = ln
+            .strip_suffix('}')
+            .and_then(|ln| ln.split_once('{'))
+            .with_context(|| format!("expected a workflow, got {ln:?}"))?;
+        let Target::Workflow(id) = label_to_target(label, &mut label_map) else {
+            anyhow::bail!("{label} can't be used as a workflow name");
+        };
+        let (conds, default) = rules.rsplit_once(',').unwrap_or(("", rules));
+        let node = Node {
+            conds: conds
+                .split(',')
+                .filter(|cond| !cond.is_empty())
+                .map(|cond| parse_cond(cond, &mut label_map))
+                .collect::<anyhow::Result<_>>()?,
+            default: label_to_target(default, &mut label_map),
+        };
+        let id = id as usize;
+        if nodes.len() <= id {
+            nodes.resize_with(id + 1, || None);
+        }
+        anyhow::ensure!(
+            nodes[id].replace(node).is_none(),
+            "workflow {label} is defined twice"
+        );
+    }
+    let Target::Workflow(start) = label_to_target("in", &mut label_map) else {
+        unreachable!()
+    };
+    let mut names = vec![String::new(); label_map.len()];
+    label_map
+        .into_iter()
+        .for_each(|(name, id)| names[id as usize] = name);
+    nodes.resize_with(names.len(), || None);
+    let nodes = nodes
+        .into_iter()
+        .zip(&names)
+        .map(|(node, name)| node.with_context(|| format!("workflow {name} is never defined")))
+        .collect::<anyhow::Result<_>>()?;
+    let workflows = Workflows {
+        names,
+        nodes,
+        start,
+    };
+    workflows.check_acyclic()?;
+    Ok(workflows)
+}
+
+impl Workflows {
+    // every step taken by the part, starting with `in` and ending with A or R
+    fn route(&self, rec: Record) -> impl Iterator<Item = Target> + '_ {
+        iter::successors(Some(Target::Workflow(self.start)), move |tgt| match *tgt {
+            Target::Workflow(id) => Some(self.nodes[id as usize].output(rec)),
+            _ => None,
+        })
+    }
+
+    // `route` and `compile` follow targets until they reach A or R, which a loop never does
+    fn check_acyclic(&self) -> anyhow::Result<()> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Visit {
+            New,
+            InProgress,
+            Done,
         }
-        if current == a {
-            sum += rec.0.iter().map(|&v| v as u64).sum::<u64>();
+        let mut visits = vec![Visit::New; self.nodes.len()];
+        for first in 0..self.nodes.len() {
+            if visits[first] != Visit::New {
+                continue;
+            }
+            visits[first] = Visit::InProgress;
+            let mut stack = vec![(first, self.targets(first))];
+            while let Some((id, targets)) = stack.last_mut() {
+                let Some(next) = targets.next() else {
+                    visits[*id] = Visit::Done;
+                    stack.pop();
+                    continue;
+                };
+                match visits[next] {
+                    Visit::New => {
+                        visits[next] = Visit::InProgress;
+                        stack.push((next, self.targets(next)));
+                    }
+                    Visit::InProgress => {
+                        anyhow::bail!("workflow {} can lead back to itself", self.names[next])
+                    }
+                    Visit::Done => {}
+                }
+            }
         }
-    });
-    sum
+        Ok(())
+    }
+
+    // the workflows a workflow can send parts to
+    fn targets(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        let node = &self.nodes[id];
+        node.conds
+            .iter()
+            .map(|cond| cond.tgt)
+            .chain([node.default])
+            .filter_map(|tgt| match tgt {
+                Target::Workflow(id) => Some(id as usize),
+                _ => None,
+            })
+    }
+
+    fn target_name(&self, tgt: Target) -> &str {
+        match tgt {
+            Target::Accept => "A",
+            Target::Reject => "R",
+            Target::Workflow(id) => &self.names[id as usize],
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 struct RangeRec([Range<u16>; 4]);
 
 impl RangeRec {
+    fn full() -> Self {
+        RangeRec(array::from_fn(|_| 1..4001))
+    }
+
     fn split(&self, elem: u8, at: u16, gt: bool) -> (Option<Self>, Option<Self>) {
         let range = &self.0[elem as usize];
-        if at > range.end {
-            return (Some(self.clone()), None);
-        }
-        if at < range.start {
-            return (None, Some(self.clone()));
-        }
+        // values below `split` go left, clamped so that neither half outgrows the range
+        let split = at.saturating_add(gt as u16).clamp(range.start, range.end);
         let mut l = self.clone();
         let l_range = &mut l.0[elem as usize];
-        l_range.end = at + gt as u16;
+        l_range.end = split;
         let mut r = self.clone();
         let r_range = &mut r.0[elem as usize];
-        r_range.start = at + gt as u16;
+        r_range.start = split;
         (
-            (l_range.len() > 0).then_some(l),
-            (r_range.len() > 0).then_some(r),
+            (l_range.start < l_range.end).then_some(l),
+            (r_range.start < r_range.end).then_some(r),
         )
     }
+
+    // returns the parts of the ranges that do and do not match the condition
+    fn split_cond(&self, cond: &Cond) -> (Option<Self>, Option<Self>) {
+        let (l, r) = self.split(cond.elem, cond.value, cond.gt);
+        if cond.gt {
+            (r, l)
+        } else {
+            (l, r)
+        }
+    }
+
+    fn volume(&self) -> u64 {
+        self.0.iter().map(|range| range.len() as u64).product()
+    }
 }
 
-#[derive(Clone, Copy)]
-enum Input {
-    Default,
-    Cond(Cond),
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Decision {
+    Accept,
+    Reject,
+    Branch {
+        workflow: u32,
+        elem: u8,
+        gt: bool,
+        value: u16,
+        yes: u32,
+        no: u32,
+    },
 }
 
-#[aoc(part = 2, example = 167409079868000)]
-fn part2(input: impl Iterator<Item = String>) -> u64 {
-    let nodes = parse_nodes(input);
-    let mut rev_nodes = HashMap::<u32, Vec<(SmallVec<[Cond; 4]>,Input, u32)>>::new();
-    nodes
-        .iter()
-        .flat_map(|(&id, node)| {
-            node.conds
-                .iter()
-                .enumerate()
-                .map(move |(i, cond)| (cond.tgt, node.conds[..i].into(), Input::Cond(*cond), id))
-                .chain(iter::once((node.default, node.conds.clone(), Input::Default, id)))
-        })
-        .for_each(|(dst, prev_nodes, input, src)| rev_nodes.entry(dst).or_default().push((prev_nodes, input, src)));
-    let start = parse_label("in");
-    let end = parse_label("A");
-    let mut in_path = HashSet::new();
-    in_path.insert(end);
-    let mut stack = vec![end];
-    while let Some(current) = stack.pop() {
-        if current == start {
-            continue;
+/// Every workflow inlined into a single decision DAG starting at `in`.
+/// Conditions that can't fail (or can't succeed) given the checks made before them are
+/// dropped, and identical subtrees are shared, so branches that can only reject collapse.
+struct DecisionTree {
+    nodes: Vec<Decision>,
+    root: u32,
+}
+
+struct TreeBuilder<'a> {
+    workflows: &'a Workflows,
+    nodes: Vec<Decision>,
+    known: HashMap<Decision, u32>,
+}
+
+impl TreeBuilder<'_> {
+    fn push(&mut self, decision: Decision) -> u32 {
+        if let Some(&id) = self.known.get(&decision) {
+            return id;
         }
-        for (_, _, id) in &rev_nodes[&current] {
-            if in_path.contains(id) {
-                continue;
+        let id = self.nodes.len() as u32;
+        self.nodes.push(decision);
+        self.known.insert(decision, id);
+        id
+    }
+
+    fn compile_target(&mut self, tgt: Target, rec: RangeRec) -> u32 {
+        match tgt {
+            Target::Accept => self.push(Decision::Accept),
+            Target::Reject => self.push(Decision::Reject),
+            Target::Workflow(id) => self.compile_workflow(id, 0, rec),
+        }
+    }
+
+    fn compile_workflow(&mut self, workflow: u32, cond_ndx: usize, rec: RangeRec) -> u32 {
+        let node = &self.workflows.nodes[workflow as usize];
+        let Some(cond) = node.conds.get(cond_ndx).copied() else {
+            return self.compile_target(node.default, rec);
+        };
+        match rec.split_cond(&cond) {
+            (Some(yes), None) => self.compile_target(cond.tgt, yes),
+            (None, Some(no)) => self.compile_workflow(workflow, cond_ndx + 1, no),
+            (Some(yes_rec), Some(no_rec)) => {
+                let yes = self.compile_target(cond.tgt, yes_rec);
+                let no = self.compile_workflow(workflow, cond_ndx + 1, no_rec);
+                if yes == no {
+                    return yes;
+                }
+                self.push(Decision::Branch {
+                    workflow,
+                    elem: cond.elem,
+                    gt: cond.gt,
+                    value: cond.value,
+                    yes,
+                    no,
+                })
             }
-            in_path.insert(*id);
-            stack.push(*id);
+            (None, None) => self.push(Decision::Reject),
         }
     }
+}
 
-    let mut stack = vec![(start, RangeRec(array::from_fn(|_| 1..4001)))];
-    let mut total = 0;
-    'outer: while let Some((current, mut rec)) = stack.pop() {
-        if current == end {
-            total += rec.0.into_iter().fold(1, |acc, range| acc * range.len() as u64);
-            continue;
+impl Workflows {
+    fn compile(&self, bounds: RangeRec) -> DecisionTree {
+        let mut builder = TreeBuilder {
+            workflows: self,
+            nodes: Vec::new(),
+            known: HashMap::new(),
+        };
+        let root = builder.compile_workflow(self.start, 0, bounds);
+        DecisionTree {
+            nodes: builder.nodes,
+            root,
         }
-        let node = &nodes[&current];
-        for cond in &node.conds {
-            let (l, r) = rec.split(cond.elem, cond.value, cond.gt);
-            let (yes, no) = if cond.gt {(r, l)} else {(l, r)};
-            if in_path.contains(&cond.tgt) {
-                if let Some(next_rec) = yes {
-                    stack.push((cond.tgt, next_rec));
+    }
+}
+
+impl DecisionTree {
+    fn accepted(&self, rec: RangeRec) -> Vec<RangeRec> {
+        let mut stack = vec![(self.root, rec)];
+        let mut accepted = Vec::new();
+        while let Some((id, rec)) = stack.pop() {
+            match self.nodes[id as usize] {
+                Decision::Accept => accepted.push(rec),
+                Decision::Reject => (),
+                Decision::Branch {
+                    elem,
+                    gt,
+                    value,
+                    yes,
+                    no,
+                    ..
+                } => {
+                    let cond = Cond {
+                        elem,
+                        gt,
+                        value,
+                        tgt: Target::Reject,
+                    };
+                    let (yes_rec, no_rec) = rec.split_cond(&cond);
+                    stack.extend(yes_rec.map(|rec| (yes, rec)));
+                    stack.extend(no_rec.map(|rec| (no, rec)));
                 }
             }
-            let Some(next_rec) = no else {
-                continue 'outer;
+        }
+        accepted
+    }
+
+    fn to_dot(&self, names: &[String]) -> String {
+        let mut out = String::from("digraph workflows {\n");
+        for (id, decision) in self.nodes.iter().enumerate() {
+            let _ = match *decision {
+                Decision::Accept => writeln!(out, "  n{id} [label=\"A\", shape=box, color=green];"),
+                Decision::Reject => writeln!(out, "  n{id} [label=\"R\", shape=box, color=red];"),
+                Decision::Branch {
+                    workflow,
+                    elem,
+                    gt,
+                    value,
+                    yes,
+                    no,
+                } => writeln!(
+                    out,
+                    concat!(
+                        "  n{id} [label=\"{}: {}{}{}\"];\n",
+                        "  n{id} -> n{yes} [label=\"yes\"];\n",
+                        "  n{id} -> n{no} [label=\"no\", style=dashed];",
+                    ),
+                    names[workflow as usize],
+                    ELEMS[elem as usize],
+                    if gt { '>' } else { '<' },
+                    value,
+                    id = id,
+                    yes = yes,
+                    no = no,
+                ),
             };
-            rec = next_rec;
         }
-        if in_path.contains(&node.default) {
-            stack.push((node.default, rec));
+        let _ = writeln!(out, "  root -> n{};\n}}", self.root);
+        out
+    }
+}
+
+pub fn export_dot(input: &str) -> anyhow::Result<String> {
+    let workflows = parse_workflows(input.lines()).context("invalid workflows")?;
+    Ok(workflows.compile(RangeRec::full()).to_dot(&workflows.names))
+}
+
+pub fn list_accepted(input: &str) -> anyhow::Result<String> {
+    let workflows = parse_workflows(input.lines()).context("invalid workflows")?;
+    let mut out = String::new();
+    for rec in workflows
+        .compile(RangeRec::full())
+        .accepted(RangeRec::full())
+    {
+        for (name, range) in ELEMS.iter().zip(&rec.0) {
+            write!(out, "{name}={}..={} ", range.start, range.end - 1)?;
+        }
+        writeln!(out, "({} combinations)", rec.volume())?;
+    }
+    Ok(out)
+}
+
+// parts as CSV rows, with an optional header naming the x, m, a and s columns
+fn parse_csv_parts(csv: &str) -> anyhow::Result<Vec<Record>> {
+    let mut lines = csv.lines().filter(|ln| !ln.trim().is_empty()).peekable();
+    let mut columns = [0, 1, 2, 3];
+    if let Some(header) = lines.next_if(|ln| !ln.starts_with(|c: char| c.is_ascii_digit())) {
+        let names = header.split(',').map(str::trim).collect_vec();
+        for (elem, col) in ELEMS.iter().zip(&mut columns) {
+            *col = names
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(elem))
+                .with_context(|| format!("missing column {elem:?}"))?;
         }
     }
+    lines
+        .enumerate()
+        .map(|(i, ln)| {
+            let values = ln
+                .split(',')
+                .map(|v| v.trim().parse::<u16>())
+                .collect::<Result<Vec<_>, _>>()
+                .with_context(|| format!("invalid part {}: {ln:?}", i + 1))?;
+            let mut rec = [0; 4];
+            for (v, &col) in rec.iter_mut().zip(&columns) {
+                *v = *values
+                    .get(col)
+                    .with_context(|| format!("part {} is missing a rating", i + 1))?;
+            }
+            Ok(Record(rec))
+        })
+        .collect()
+}
+
+pub fn classify_csv(input: &str, csv: &str) -> anyhow::Result<String> {
+    let workflows = parse_workflows(input.lines()).context("invalid workflows")?;
+    let mut out = String::from("x,m,a,s,result,path\n");
+    for rec in parse_csv_parts(csv)? {
+        let route = workflows.route(rec).collect_vec();
+        let Some((&result, path)) = route.split_last() else {
+            continue;
+        };
+        writeln!(
+            out,
+            "{},{},{}",
+            rec.0.iter().join(","),
+            workflows.target_name(result),
+            path.iter().map(|&tgt| workflows.target_name(tgt)).join(">"),
+        )?;
+    }
+    Ok(out)
+}
+
+// bounds are given as e.g. `x=1-2000`, ratings without bounds can be anything from 1 to 4000
+pub fn count_accepted(input: &str, bounds: &[String]) -> anyhow::Result<u64> {
+    let workflows = parse_workflows(input.lines()).context("invalid workflows")?;
+    let mut rec = RangeRec::full();
+    for bound in bounds {
+        let (elem, range) = bound
+            .split_once('=')
+            .with_context(|| format!("expected <rating>=<min>-<max>, got {bound:?}"))?;
+        let ndx = ELEMS
+            .iter()
+            .position(|&e| e == elem)
+            .with_context(|| format!("unknown rating {elem:?}"))?;
+        let (min, max) = range
+            .split_once('-')
+            .with_context(|| format!("expected <min>-<max>, got {range:?}"))?;
+        let (min, max) = (min.parse::<u16>()?, max.parse::<u16>()?);
+        anyhow::ensure!(min <= max && max < u16::MAX, "invalid bounds {range:?}");
+        rec.0[ndx] = min..max + 1;
+    }
+    Ok(workflows
+        .compile(rec.clone())
+        .accepted(rec)
+        .iter()
+        .map(RangeRec::volume)
+        .sum())
+}
+
+pub struct System {
+    workflows: Workflows,
+    parts: Vec<Record>,
+}
+
+impl Solution for Day19 {
+    type Parsed = System;
+    type Output = u64;
+
+    fn parse(input: &str) -> anyhow::Result<System> {
+        let mut lines = input.lines();
+        let workflows = parse_workflows(&mut lines).context("invalid workflows")?;
+        let parts = lines
+            .filter(|ln| !ln.is_empty())
+            .map(parse_rec)
+            .collect::<anyhow::Result<_>>()?;
+        Ok(System { workflows, parts })
+    }
+
+    fn part1(system: &System) -> anyhow::Result<u64> {
+        Ok(system
+            .parts
+            .iter()
+            .filter(|&&rec| system.workflows.route(rec).last() == Some(Target::Accept))
+            .map(|rec| rec.0.iter().map(|&v| v as u64).sum::<u64>())
+            .sum())
+    }
+
+    fn part2(system: &System) -> anyhow::Result<u64> {
+        Ok(system
+            .workflows
+            .compile(RangeRec::full())
+            .accepted(RangeRec::full())
+            .iter()
+            .map(RangeRec::volume)
+            .sum())
+    }
+}
+
+#[aoc(part = 1, example = 19114)]
+pub fn part1(input: &str) -> anyhow::Result<u64> {
+    parse_and_solve::<Day19>(input, Day19::part1)
+}
+
+#[aoc(part = 2, example = 167409079868000)]
+pub fn part2(input: &str) -> anyhow::Result<u64> {
+    parse_and_solve::<Day19>(input, Day19::part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_accepted_within_narrow_bounds() {
+        let bounds = ["x=1-100", "m=1-1", "a=1-1", "s=1-1"].map(String::from);
+        let count = count_accepted("in{x>101:R,x>102:R,x>101:R,A}\n", &bounds).unwrap();
+        assert_eq!(count, 100);
+    }
+
+    fn compile(input: &str) -> DecisionTree {
+        parse_workflows(input.lines())
+            .unwrap()
+            .compile(RangeRec::full())
+    }
+
+    #[test]
+    fn long_labels_stay_apart() {
+        let input =
+            "in{x<2001:workflow1,workflow2}\nworkflow1{m<2001:A,R}\nworkflow2{a<4001:R,A}\n";
+        assert_eq!(
+            count_accepted(input, &[]).unwrap(),
+            2000 * 2000 * 4000 * 4000
+        );
+    }
+
+    #[test]
+    fn dead_branches_are_dropped() {
+        // x<1000 can't hold past x>2000, and both sides of b reject
+        let tree = compile("in{x>2000:a,b}\na{x<1000:R,A}\nb{m>5:R,R}\n");
+        assert_eq!(tree.nodes.len(), 3);
+        assert!(matches!(
+            tree.nodes[tree.root as usize],
+            Decision::Branch {
+                elem: 0,
+                gt: true,
+                value: 2000,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn identical_subtrees_are_shared() {
+        // p is reached both from in and through q, and compiles to the same branch
+        let tree = compile("in{x>2000:p,q}\nq{a>5:p,R}\np{m>10:A,R}\n");
+        assert_eq!(tree.nodes.len(), 5);
+    }
+
+    #[test]
+    fn dot_output() {
+        assert_eq!(
+            export_dot("in{x>2000:A,R}\n").unwrap(),
+            concat!(
+                "digraph workflows {\n",
+                "  n0 [label=\"A\", shape=box, color=green];\n",
+                "  n1 [label=\"R\", shape=box, color=red];\n",
+                "  n2 [label=\"in: x>2000\"];\n",
+                "  n2 -> n0 [label=\"yes\"];\n",
+                "  n2 -> n1 [label=\"no\", style=dashed];\n",
+                "  root -> n2;\n",
+                "}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn accepted_ranges_add_up_to_part2() {
+        let example = EXAMPLES[0].strip_prefix('\n').unwrap();
+        let accepted = compile(example).accepted(RangeRec::full());
+        let total: u64 = accepted.iter().map(RangeRec::volume).sum();
+        assert_eq!(total, 167409079868000);
+        assert_eq!(
+            list_accepted(example).unwrap().lines().count(),
+            accepted.len()
+        );
+    }
 
-    total
+    #[test]
+    fn invalid_workflows_are_rejected() {
+        let error = |input: &str| parse_workflows(input.lines()).err().unwrap().to_string();
+        assert_eq!(
+            error("in{x>1:b,R}\nb{m<5:A,in}\n"),
+            "workflow in can lead back to itself"
+        );
+        assert_eq!(error("in{x>1:b,R}\n"), "workflow b is never defined");
+        assert_eq!(error("a{x>1:A,R}\n"), "workflow in is never defined");
+        assert_eq!(error("in{A}\nin{R}\n"), "workflow in is defined twice");
+        assert_eq!(
+            error("in{q>1:A,R}\n"),
+            "expected e.g. x>10:A, got \"q>1:A\""
+        );
+        assert!(export_dot("in{x>1:in,R}\n").is_err());
+        assert!(list_accepted("in{x>1\n").is_err());
+    }
 }
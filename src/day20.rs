@@ -1,40 +1,60 @@
 use std::{
-    array,
     collections::{HashMap, VecDeque},
+    fmt::Write,
 };
 
-use aoc_framework::*;
+use aoc_framework::{anyhow::Context, *};
 use smallvec::SmallVec;
 
+use crate::solution::{parse_and_solve, Solution};
+
 pub struct Day20;
 
-impl_day!(Day20::{part1, part2}: 2023[20], r"
+impl_day_with_examples!(Day20::{part1, part2}: 2023[20], r"
 broadcaster -> a
 %a -> inv, con
 &inv -> b
 %b -> con
 &con -> output
+",
+r"
+broadcaster -> a0, b0
+%a0 -> a1, ha
+%a1 -> a2
+%a2 -> ha
+&ha -> a0, a1, ia
+&ia -> fd
+%b0 -> b1, hb
+%b1 -> hb
+&hb -> b0, ib
+&ib -> fd
+&fd -> rx
+",
+r"
+broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a
 ");
 
-#[derive(Default)]
-struct InOut {
-    inputs: SmallVec<[u8; 5]>,
-    outputs: SmallVec<[u8; 5]>,
-}
-
 #[derive(Debug, Clone, Default)]
 enum Module {
     Broadcaster,
     #[default]
     Simple,
     FlipFlop(bool),
-    Conjunction(u64),
+    // last pulse received from each input, and how many of them were high
+    Conjunction {
+        memory: Vec<bool>,
+        high: usize,
+    },
 }
 
 use Module::*;
 
 impl Module {
-    fn handle_pulse(&mut self, pulse: bool, src: u8) -> Option<bool> {
+    fn handle_pulse(&mut self, pulse: bool, slot: usize) -> Option<bool> {
         match self {
             Broadcaster => Some(pulse),
             Simple => None,
@@ -43,98 +63,175 @@ impl Module {
                 *state = !*state;
                 Some(*state)
             }
-            Conjunction(state) => {
-                let offset = 1 << src;
-                let mask = offset * pulse as u64;
-                *state = (*state & !(offset)) | mask;
-                Some(*state != u64::MAX)
+            Conjunction { memory, high } => {
+                if memory[slot] != pulse {
+                    memory[slot] = pulse;
+                    if pulse {
+                        *high += 1;
+                    } else {
+                        *high -= 1;
+                    }
+                }
+                Some(*high != memory.len())
             }
         }
     }
 }
 
-fn name_to_id(name: &str, name_map: &mut HashMap<String, u8>) -> u8 {
-    if let Some(id) = name_map.get(name) {
-        return *id;
-    }
-    let id = name_map.len() as u8;
-    name_map.insert(name.to_string(), id);
-    id
-}
-
-fn parse_module(
-    ln: &str,
-    name_map: &mut HashMap<String, u8>,
-    inout: &mut [InOut; 64],
-) -> Option<(u8, Module)> {
-    let (module, outputs) = ln.split_once(" -> ")?;
-    let (typ, name) = match module.as_bytes()[0] {
-        typ @ (b'%' | b'&') => (typ, &module[1..]),
-        _ => (0, module),
-    };
-    let id = name_to_id(name, name_map);
-    for output in outputs.split(", ") {
-        let output_id = name_to_id(output, name_map);
-        inout[output_id as usize].inputs.push(id);
-        inout[id as usize].outputs.push(output_id);
-    }
-    Some((
-        id,
-        match (typ, name) {
-            (_, "broadcaster") => Broadcaster,
-            (b'%', _) => FlipFlop(false),
-            (b'&', _) => Conjunction(u64::MAX),
-            _ => Simple,
-        },
-    ))
-}
-
-fn setup(input: impl Iterator<Item = String>) -> (HashMap<String, u8>, [InOut; 64], [Module; 64]) {
-    let mut name_map = HashMap::new();
-    let mut inout = array::from_fn(|_| InOut::default());
-    let mut modules: [Module; 64] = array::from_fn(|_| Default::default());
-    input
-        .flat_map(|ln| parse_module(&ln, &mut name_map, &mut inout))
-        .for_each(|(id, module)| modules[id as usize] = module);
-
-    for (module, inout) in modules.iter_mut().zip(&inout) {
-        if let Conjunction(state) = module {
-            inout.inputs.iter().for_each(|module| *state &= !(1 << *module));
-        }
-    }
-    (name_map, inout, modules)
+/// A pulse sent between two modules, which are identified by their index in `Network::names`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pulse {
+    pub src: usize,
+    pub dst: usize,
+    pub high: bool,
+    /// Which of `dst`'s inputs the pulse arrives on.
+    pub slot: usize,
 }
 
-#[aoc(part = 1, example = 11687500)]
-fn part1(input: impl Iterator<Item = String>) -> u64 {
-    let (name_map, inout, mut modules) = setup(input);
+#[derive(Clone)]
+pub struct Network {
+    name_map: HashMap<String, usize>,
+    names: Vec<String>,
+    modules: Vec<Module>,
+    inputs: Vec<SmallVec<[usize; 5]>>,
+    // destination and input slot in the destination
+    outputs: Vec<SmallVec<[(usize, usize); 5]>>,
+    // low and high pulses received by each module
+    received: Vec<[u64; 2]>,
+    queue: VecDeque<Pulse>,
+    button: usize,
+    broadcaster: usize,
+}
 
-    let broadcaster_id = name_map["broadcaster"];
-    let mut low_pulses = 0;
-    let mut high_pulses = 0;
-    let mut queue = VecDeque::new();
+impl Network {
+    fn module_id(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.name_map.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.name_map.insert(name.to_string(), id);
+        self.names.push(name.to_string());
+        self.modules.push(Simple);
+        self.inputs.push(SmallVec::new());
+        self.outputs.push(SmallVec::new());
+        self.received.push([0; 2]);
+        id
+    }
 
-    for _ in 0..1000 {
-        queue.push_back((false, broadcaster_id, 0));
+    fn connect(&mut self, src: usize, dst: usize) {
+        let slot = self.inputs[dst].len();
+        self.inputs[dst].push(src);
+        self.outputs[src].push((dst, slot));
+    }
 
-        while let Some((pulse, id, src)) = queue.pop_front() {
-                if pulse {
-                    high_pulses += 1;
-                } else {
-                    low_pulses += 1;
-                }
-            let result = modules[id as usize]
-                .handle_pulse(pulse, src);
-            let Some(pulse) = result else {
+    pub fn parse(input: &str) -> Self {
+        let mut network = Network {
+            name_map: HashMap::new(),
+            names: Vec::new(),
+            modules: Vec::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            received: Vec::new(),
+            queue: VecDeque::new(),
+            button: 0,
+            broadcaster: 0,
+        };
+        network.button = network.module_id("button");
+        network.broadcaster = network.module_id("broadcaster");
+        network.connect(network.button, network.broadcaster);
+        for ln in input.lines() {
+            let Some((module, outputs)) = ln.split_once(" -> ") else {
                 continue;
             };
-            let mod_inout = &inout[id as usize];
-            for output in &mod_inout.outputs {
-                queue.push_back((pulse, *output, id));
+            let (typ, name) = match module.as_bytes()[0] {
+                typ @ (b'%' | b'&') => (typ, &module[1..]),
+                _ => (0, module),
+            };
+            let id = network.module_id(name);
+            for output in outputs.split(", ") {
+                let output_id = network.module_id(output);
+                network.connect(id, output_id);
             }
+            network.modules[id] = match (typ, name) {
+                (_, "broadcaster") => Broadcaster,
+                (b'%', _) => FlipFlop(false),
+                (b'&', _) => Conjunction {
+                    memory: Vec::new(),
+                    high: 0,
+                },
+                _ => Simple,
+            };
+        }
+        for (module, inputs) in network.modules.iter_mut().zip(&network.inputs) {
+            if let Conjunction { memory, .. } = module {
+                *memory = vec![false; inputs.len()];
+            }
+        }
+        network
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.name_map.get(name).copied()
+    }
+
+    /// Every module, including the button, indexed by id.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Low and high pulses received by the module so far.
+    pub fn received(&self, id: usize) -> [u64; 2] {
+        self.received[id]
+    }
+
+    /// Queues the low pulse the button sends to the broadcaster, `step` then delivers it.
+    pub fn press_button(&mut self) {
+        self.queue.push_back(Pulse {
+            src: self.button,
+            dst: self.broadcaster,
+            high: false,
+            slot: 0,
+        });
+    }
+
+    /// Delivers the next pending pulse and returns it, or `None` once the network is idle.
+    pub fn step(&mut self) -> Option<Pulse> {
+        let pulse = self.queue.pop_front()?;
+        self.received[pulse.dst][pulse.high as usize] += 1;
+        if let Some(high) = self.modules[pulse.dst].handle_pulse(pulse.high, pulse.slot) {
+            let src = pulse.dst;
+            self.queue
+                .extend(self.outputs[src].iter().map(|&(dst, slot)| Pulse {
+                    src,
+                    dst,
+                    high,
+                    slot,
+                }));
         }
+        Some(pulse)
     }
-    low_pulses * high_pulses
+}
+
+pub fn trace(input: &str, presses: usize) -> String {
+    let mut network = Network::parse(input);
+    let mut out = String::new();
+    for _ in 0..presses {
+        network.press_button();
+        while let Some(Pulse { src, dst, high, .. }) = network.step() {
+            let level = if high { "high" } else { "low" };
+            let _ = writeln!(
+                out,
+                "{} -{level}-> {}",
+                network.names[src], network.names[dst]
+            );
+        }
+    }
+    out.push_str("\nmodule: low high\n");
+    for (id, name) in network.names().iter().enumerate() {
+        let [low, high] = network.received(id);
+        let _ = writeln!(out, "{name}: {low} {high}");
+    }
+    out
 }
 
 fn gcd(mut a: u64, mut b: u64) -> u64 {
@@ -148,41 +245,329 @@ fn lcm(a: u64, b: u64) -> u64 {
     (a * b) / gcd(a, b)
 }
 
-#[aoc(part = 2)]
-fn part2(input: impl Iterator<Item = String>) -> u64 {
-    let (name_map, inout, mut modules) = setup(input);
+// a chain of flip-flops counting button presses in binary, with a conjunction (the hub)
+// watching some of the bits and resetting the others once the count reaches `period`
+struct Counter {
+    hub: usize,
+    // output of the hub, inverted so it sends a high pulse on every reset
+    inverter: usize,
+    // flip-flops from least to most significant bit
+    bits: Vec<usize>,
+    period: u64,
+}
 
-    let broadcaster_id = name_map["broadcaster"];
-    let rx_id = name_map["rx"];
-    let rx_input = &inout[rx_id as usize].inputs;
-    assert_eq!(rx_input.len(), 1);
-    assert!(matches!(modules[rx_input[0] as usize], Conjunction(_)));
-    let inputs = &inout[rx_input[0] as usize].inputs;
-    let mut is_conj_input = [false; 64];
-    inputs.iter().for_each(|&module| is_conj_input[module as usize] = true);
-    let mut input_cycles = vec![0; inputs.len()];
-    let mut queue = VecDeque::new();
+impl Network {
+    fn is_conjunction(&self, id: usize) -> bool {
+        matches!(self.modules[id], Conjunction { .. })
+    }
 
-    for i in 1.. {
-        queue.push_back((false, broadcaster_id, 0));
+    fn sends_to(&self, src: usize, dst: usize) -> bool {
+        self.outputs[src].iter().any(|&(id, _)| id == dst)
+    }
 
-        while let Some((pulse, id, src)) = queue.pop_front() {
-            let result = modules[id as usize].handle_pulse(pulse, src);
-            let Some(pulse) = result else {
-                continue;
-            };
-            if pulse && is_conj_input[id as usize] {
-                let pos = inputs.iter().position(|inp| *inp == id).unwrap();
-                input_cycles[pos] = i;
-                if input_cycles.iter().all(|cycle| *cycle != 0) {
-                    return input_cycles.into_iter().reduce(lcm).unwrap_or(0);
+    fn read_counter(&self, start: usize, feeder: usize) -> anyhow::Result<Counter> {
+        let name = |id: usize| &self.names[id];
+        anyhow::ensure!(
+            matches!(self.modules[start], FlipFlop(_)),
+            "{} is fed by the broadcaster but isn't a flip-flop",
+            name(start)
+        );
+        let mut bits = vec![start];
+        let mut hub = None;
+        let mut current = start;
+        loop {
+            let mut next = None;
+            for &(dst, _) in &self.outputs[current] {
+                match self.modules[dst] {
+                    FlipFlop(_) if next.is_none() => next = Some(dst),
+                    Conjunction { .. } if hub.is_none() || hub == Some(dst) => hub = Some(dst),
+                    _ => anyhow::bail!(
+                        "{} doesn't fit in a counter, its outputs are {}",
+                        name(current),
+                        self.outputs[current]
+                            .iter()
+                            .map(|&(id, _)| name(id))
+                            .join(", ")
+                    ),
                 }
             }
-            let mod_inout = &inout[id as usize];
-            for output in &mod_inout.outputs {
-                queue.push_back((pulse, *output, id));
+            let Some(next) = next else {
+                break;
+            };
+            anyhow::ensure!(
+                !bits.contains(&next),
+                "flip-flop chain starting at {} loops back on itself",
+                name(start)
+            );
+            bits.push(next);
+            current = next;
+        }
+        let hub = hub.with_context(|| {
+            format!(
+                "flip-flop chain starting at {} isn't watched by a conjunction",
+                name(start)
+            )
+        })?;
+
+        let mut period = 0;
+        for (i, &bit) in bits.iter().enumerate() {
+            let watched = self.sends_to(bit, hub);
+            let reset = self.sends_to(hub, bit);
+            // the hub fires when all watched bits are set, it then has to flip every other bit
+            // and the lowest one so the carry wraps the counter back to 0
+            anyhow::ensure!(
+                if i == 0 {
+                    watched && reset
+                } else {
+                    watched != reset
+                },
+                "{} (bit {i} of the counter watched by {}) is {}",
+                name(bit),
+                name(hub),
+                if watched {
+                    "both watched and reset"
+                } else {
+                    "neither watched nor reset"
+                }
+            );
+            if watched {
+                period |= 1 << i;
             }
         }
+        anyhow::ensure!(
+            self.inputs[hub].iter().all(|input| bits.contains(input)),
+            "{} watches modules outside of its counter",
+            name(hub)
+        );
+
+        let outputs = self.outputs[hub]
+            .iter()
+            .map(|&(id, _)| id)
+            .filter(|id| !bits.contains(id))
+            .collect_vec();
+        let [inverter] = outputs[..] else {
+            anyhow::bail!(
+                "{} should have a single output besides its counter, it has {}",
+                name(hub),
+                outputs.len()
+            );
+        };
+        anyhow::ensure!(
+            self.is_conjunction(inverter)
+                && self.inputs[inverter].len() == 1
+                && self.outputs[inverter].len() == 1
+                && self.sends_to(inverter, feeder),
+            "{} should go through an inverter to reach {}",
+            name(hub),
+            name(feeder)
+        );
+        Ok(Counter {
+            hub,
+            inverter,
+            bits,
+            period,
+        })
+    }
+
+    // rx gets a low pulse when every counter resets on the same press
+    fn analyse(&self) -> anyhow::Result<Vec<Counter>> {
+        let rx = self.id("rx").context("the network has no rx module")?;
+        let [feeder] = self.inputs[rx][..] else {
+            anyhow::bail!(
+                "rx should have a single input, it has {}",
+                self.inputs[rx].len()
+            );
+        };
+        anyhow::ensure!(
+            self.is_conjunction(feeder),
+            "rx is fed by {}, which isn't a conjunction",
+            self.names[feeder]
+        );
+        let counters = self.outputs[self.broadcaster]
+            .iter()
+            .map(|&(start, _)| self.read_counter(start, feeder))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let inverters = counters
+            .iter()
+            .map(|counter| counter.inverter)
+            .sorted()
+            .collect_vec();
+        let feeder_inputs = self.inputs[feeder].iter().copied().sorted().collect_vec();
+        anyhow::ensure!(
+            inverters == feeder_inputs,
+            "{} has inputs that don't come from a counter",
+            self.names[feeder]
+        );
+        Ok(counters)
+    }
+}
+
+pub fn analyse(input: &str) -> anyhow::Result<String> {
+    let network = Network::parse(input);
+    let counters = network.analyse()?;
+    let mut out = String::new();
+    for counter in &counters {
+        let bits = counter
+            .bits
+            .iter()
+            .rev()
+            .map(|&bit| {
+                if network.sends_to(bit, counter.hub) {
+                    '1'
+                } else {
+                    '0'
+                }
+            })
+            .collect::<String>();
+        writeln!(
+            out,
+            "{} ({} bits, via {}): {bits} = {}",
+            network.names[counter.hub],
+            counter.bits.len(),
+            network.names[counter.inverter],
+            counter.period
+        )?;
+    }
+    let total = counters.iter().map(|counter| counter.period).reduce(lcm);
+    writeln!(
+        out,
+        "rx receives a low pulse after {} presses",
+        total.unwrap_or(0)
+    )?;
+    Ok(out)
+}
+
+// part 2 reads the answer off the counters, which needs rx to hang off a single conjunction
+// collecting one counter per input
+pub fn check_assumptions(input: &str) -> Vec<(&'static str, bool)> {
+    let network = Network::parse(input);
+    let rx = network.id("rx");
+    let feeders = rx.map_or(&[][..], |rx| &network.inputs[rx][..]);
+    vec![
+        ("there is an rx module", rx.is_some()),
+        ("rx has a single input", feeders.len() == 1),
+        (
+            "rx is fed by a conjunction",
+            matches!(feeders, [feeder] if network.is_conjunction(*feeder)),
+        ),
+        (
+            "every input of that conjunction is driven by a binary counter",
+            network.analyse().is_ok(),
+        ),
+    ]
+}
+
+impl Solution for Day20 {
+    type Parsed = Network;
+    type Output = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Network> {
+        Ok(Network::parse(input))
+    }
+
+    fn part1(network: &Network) -> anyhow::Result<u64> {
+        let mut network = network.clone();
+        for _ in 0..1000 {
+            network.press_button();
+            while network.step().is_some() {}
+        }
+        let (low_pulses, high_pulses) = network
+            .received
+            .iter()
+            .fold((0, 0), |(low, high), [l, h]| (low + l, high + h));
+        Ok(low_pulses * high_pulses)
+    }
+
+    fn part2(network: &Network) -> anyhow::Result<u64> {
+        network
+            .analyse()?
+            .iter()
+            .map(|counter| counter.period)
+            .reduce(lcm)
+            .context("rx has no counters feeding it")
+    }
+}
+
+#[aoc(part = 1, example = 11687500)]
+pub fn part1(input: &str) -> anyhow::Result<u64> {
+    parse_and_solve::<Day20>(input, Day20::part1)
+}
+
+// a made-up network with counters of period 5 and 3
+#[aoc(part = 2, example = 15)]
+pub fn part2(input: &str) -> anyhow::Result<u64> {
+    parse_and_solve::<Day20>(input, Day20::part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pulses_of_one_press() {
+        let mut network = Network::parse(EXAMPLES[2]);
+        network.press_button();
+        let pulses = std::iter::from_fn(|| network.step()).collect_vec();
+        let names = network.names();
+        let pulses = pulses
+            .iter()
+            .map(|pulse| {
+                (
+                    names[pulse.src].as_str(),
+                    names[pulse.dst].as_str(),
+                    pulse.high,
+                )
+            })
+            .collect_vec();
+        assert_eq!(
+            pulses,
+            [
+                ("button", "broadcaster", false),
+                ("broadcaster", "a", false),
+                ("broadcaster", "b", false),
+                ("broadcaster", "c", false),
+                ("a", "b", true),
+                ("b", "c", true),
+                ("c", "inv", true),
+                ("inv", "a", false),
+                ("a", "b", false),
+                ("b", "c", false),
+                ("c", "inv", false),
+                ("inv", "a", true),
+            ]
+        );
+        let inv = network.id("inv").unwrap();
+        assert_eq!(network.received(inv), [1, 1]);
+        assert_eq!(network.received(network.id("b").unwrap()), [2, 1]);
+    }
+
+    fn analyse_error(input: &str) -> String {
+        let network = Network::parse(input);
+        network.analyse().err().unwrap().to_string()
+    }
+
+    #[test]
+    fn analyse_rejects_other_networks() {
+        assert_eq!(analyse_error(EXAMPLES[0]), "the network has no rx module");
+        assert_eq!(
+            analyse_error("broadcaster -> a, b\n%a -> rx\n%b -> rx\n"),
+            "rx should have a single input, it has 2"
+        );
+        assert_eq!(
+            analyse_error("broadcaster -> a\n%a -> rx\n"),
+            "rx is fed by a, which isn't a conjunction"
+        );
+        assert_eq!(
+            analyse_error(&format!("{}%stray -> ha\n", EXAMPLES[1])),
+            "ha watches modules outside of its counter"
+        );
+        assert_eq!(
+            analyse_error("broadcaster -> a\n%a -> b\n%b -> a\n&fd -> rx\n"),
+            "flip-flop chain starting at a loops back on itself"
+        );
+        assert_eq!(
+            analyse_error(&EXAMPLES[1].replace("&ha -> a0, a1,", "&ha -> a0, a1, a2,")),
+            "a2 (bit 2 of the counter watched by ha) is both watched and reset"
+        );
     }
-    0
 }
@@ -1,15 +1,14 @@
-use std::collections::VecDeque;
-
 use aoc_framework::{
-    direction::Direction,
     grid::{Grid, GridView},
     point::{Point, Point2},
     *,
 };
 
+use crate::grid_search;
+
 pub struct Day21;
 
-impl_day!(Day21::{part1, part2}: 2023[21], r"
+impl_day_with_examples!(Day21::{part1, part2}: 2023[21], r"
 ...........
 .....###.#.
 .###.##..#.
@@ -23,100 +22,118 @@ impl_day!(Day21::{part1, part2}: 2023[21], r"
 ...........
 ");
 
-#[aoc(part = 1, example = 16)]
-fn part1(input: Vec<u8>) -> u64 {
-    let target = if input.len() < 1000 { 6 } else { 64 };
-    let mut g = Grid::from_bytes(input);
-    let start = g.offset_to_point(g.data().iter().position(|&b| b == b'S').unwrap());
-    let mut queue = VecDeque::new();
-    queue.push_back((start, 0));
-    while let Some((pos, dist)) = queue.pop_front() {
-        if dist == target {
-            continue;
-        }
-        for i in 0..4 {
-            let neighbor = pos + Direction::new(i);
-            if g.get(neighbor) != Some(&b'.') {
-                continue;
-            }
-            if dist % 2 == 0 {
-                g.set(neighbor, b'o');
-            } else {
-                g.set(neighbor, b'O');
-            }
-            queue.push_back((neighbor, dist + 1))
-        }
-    }
-    g.data().iter().filter(|&&b| b == b'O').count() as u64 + 1
+const PART1_STEPS: u64 = 64;
+const PART2_STEPS: u64 = 26501365;
+// small enough to walk the tiled garden directly
+const BRUTE_FORCE_STEPS: u64 = 1000;
+
+fn find_start(g: &GridView<'_, u8, 2>) -> Option<Point2> {
+    let ndx = g.data().iter().position(|&b| b == b'S')?;
+    Some(g.offset_to_point(ndx))
 }
 
-fn fill(g: &GridView<'_, u8, 2>, start: Point2, second_start: Option<Point2>, target: u64) -> u64 {
-    let mut g = g.clone().to_owned();
-    let mut queue = VecDeque::new();
-    queue.push_back((start, 0));
-    queue.extend(second_start.map(|p| (p, 0)));
-    let rem = target % 2;
-    while let Some((pos, dist)) = queue.pop_front() {
-        if dist == target {
-            continue;
-        }
-        for i in 0..4 {
-            let neighbor = pos + Direction::new(i);
-            if g.get(neighbor) != Some(&b'.') {
-                continue;
-            }
-            if dist % 2 == rem {
-                g.set(neighbor, b'o');
-            } else {
-                g.set(neighbor, b'O');
-            }
-            queue.push_back((neighbor, dist + 1))
-        }
-    }
-    g.data().iter().filter(|&&b| b == b'O').count() as u64
+// number of plots first reached after each number of steps, with the garden tiled infinitely
+fn plots_by_distance(g: &GridView<'_, u8, 2>, start: Point2, max_steps: u64) -> Vec<u64> {
+    grid_search::tiled_layer_sizes(g, [start], |&b| b != b'#', max_steps)
 }
 
-#[aoc(part = 2)]
-fn part2(input: Vec<u8>) -> u64 {
-    let target = 26501365;
-    let mut g = Grid::from_bytes(input);
-    let start = g.offset_to_point(g.data().iter().position(|&b| b == b'S').unwrap());
-    g.set(start, b'.');
+// a plot reached in n steps can also be reached in n + 2, n + 4... by stepping back and forth
+fn reachable(counts: &[u64], steps: u64) -> u64 {
+    counts[..=steps as usize]
+        .iter()
+        .skip((steps % 2) as usize)
+        .step_by(2)
+        .sum()
+}
+
+// the shape of the real inputs that makes the number of reachable plots grow quadratically
+// every time the step count increases by the garden width, sampled where the walk just reaches
+// the edge of a garden copy
+fn extrapolation_checks(
+    g: &GridView<'_, u8, 2>,
+    start: Point2,
+    steps: u64,
+) -> [(&'static str, bool); 6] {
     let Point([w, h]) = g.size();
-    let width = w as u64;
-    let fully_filled_count = (target - width / 2) / width;
-    let left = Point([-1, h / 2]);
-    let bottom = Point([w / 2, h]);
-    let right = Point([w, h / 2]);
-    let top = Point([w / 2, -1]);
+    let half = (w / 2) as u64;
+    [
+        ("square garden", w == h),
+        ("odd garden width", w % 2 == 1),
+        ("S in the centre", start == Point([w / 2, h / 2])),
+        (
+            "no rocks in the row of S",
+            (0..w).all(|x| g[(x, start.y())] != b'#'),
+        ),
+        (
+            "no rocks in the column of S",
+            (0..h).all(|y| g[(start.x(), y)] != b'#'),
+        ),
+        (
+            "the steps end on the edge of a garden copy",
+            steps
+                .checked_sub(half)
+                .is_some_and(|s| s.is_multiple_of(w as u64)),
+        ),
+    ]
+}
 
-    let filled_odds = fill(&g, start, None, target);
-    let filled_evens = fill(&g, left, None, target - 65);
+// fits a quadratic to the plots reached after half a garden width plus 1, 2, 3 and 4 widths
+fn extrapolate(g: &GridView<'_, u8, 2>, start: Point2, steps: u64) -> anyhow::Result<u64> {
+    let width = g.size().x() as u64;
+    let sample = |k: u64| width / 2 + k * width;
+    let counts = plots_by_distance(g, start, sample(4));
+    let [y1, y2, y3, y4] = [1, 2, 3, 4].map(|k| reachable(&counts, sample(k)) as i64);
+    let (d1, d2) = (y2 - y1, y3 - 2 * y2 + y1);
+    let fit = |k: i64| y1 + (k - 1) * d1 + (k - 1) * (k - 2) / 2 * d2;
+    anyhow::ensure!(
+        fit(4) == y4,
+        "the number of reachable plots doesn't grow quadratically ({} predicted, {y4} reached)",
+        fit(4)
+    );
+    Ok(fit(((steps - width / 2) / width) as i64) as u64)
+}
 
-    let mut num_odds = fully_filled_count - 1;
-    for i in 1..fully_filled_count {
-        num_odds += 2 * (fully_filled_count - i - 1);
+fn count_reachable(g: &GridView<'_, u8, 2>, steps: u64) -> anyhow::Result<u64> {
+    let Some(start) = find_start(g) else {
+        anyhow::bail!("the garden has no starting position");
+    };
+    let width = g.size().x() as u64;
+    if steps <= (width / 2 + 4 * width).max(BRUTE_FORCE_STEPS) {
+        return Ok(reachable(&plots_by_distance(g, start, steps), steps));
     }
-    let mut num_evens = fully_filled_count;
-    for i in 1..fully_filled_count {
-        num_evens += 2 * (fully_filled_count - i);
+    let checks = extrapolation_checks(g, start, steps);
+    if let Some((assumption, _)) = checks.into_iter().find(|(_, ok)| !ok) {
+        anyhow::bail!("can't extrapolate to {steps} steps, assumption failed: {assumption}");
     }
-    let mut total = num_odds * filled_odds + num_evens * filled_evens;
+    extrapolate(g, start, steps)
+}
 
-    total += fill(&g, left, None, 131);
-    total += fill(&g, bottom, None, 131);
-    total += fill(&g, right, None, 131);
-    total += fill(&g, top, None, 131);
+/// Part 2 extrapolates from a few garden widths, which only works on gardens of the puzzle's
+/// shape and only if the step count lands on the edge of a garden copy.
+pub fn check_assumptions(input: &str) -> Vec<(&'static str, bool)> {
+    let g = Grid::from_bytes(input.as_bytes().to_vec());
+    let Some(start) = find_start(&g) else {
+        return vec![("there is an S", false)];
+    };
+    let mut checks = vec![("there is an S", true)];
+    checks.extend(extrapolation_checks(&g, start, PART2_STEPS));
+    checks.push((
+        "the number of reachable plots grows quadratically",
+        extrapolate(&g, start, PART2_STEPS).is_ok(),
+    ));
+    checks
+}
 
-    total += fill(&g, Point([-1, h - 1]), None, 65) * fully_filled_count;
-    total += fill(&g, Point([w - 1, h]), None, 65) * fully_filled_count;
-    total += fill(&g, Point([-1, 0]), None, 65) * fully_filled_count;
-    total += fill(&g, Point([w - 1, -1]), None, 65) * fully_filled_count;
+pub fn reachable_plots(input: &str, steps: u64) -> anyhow::Result<u64> {
+    count_reachable(&Grid::from_bytes(input.as_bytes().to_vec()), steps)
+}
 
-    total += fill(&g, left, Some(bottom), 131) * (fully_filled_count - 1);
-    total += fill(&g, bottom, Some(right), 131) * (fully_filled_count - 1);
-    total += fill(&g, right, Some(top), 131) * (fully_filled_count - 1);
-    total += fill(&g, top, Some(left), 131) * (fully_filled_count - 1);
+#[aoc(part = 1)]
+pub fn part1(input: Vec<u8>) -> anyhow::Result<u64> {
+    count_reachable(&Grid::from_bytes(input), PART1_STEPS)
+}
 
-    total
+#[aoc(part = 2)]
+pub fn part2(input: Vec<u8>) -> anyhow::Result<u64> {
+    count_reachable(&Grid::from_bytes(input), PART2_STEPS)
 }
@@ -0,0 +1,114 @@
+use aoc_framework::*;
+
+pub struct Day22;
+
+impl_day_with_examples!(Day22::{part1, part2}: 2023[22], r"
+1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9
+");
+
+struct Brick {
+    min: [usize; 3],
+    max: [usize; 3],
+}
+
+fn parse_brick(line: &str) -> Option<Brick> {
+    let (a, b) = line.split_once('~')?;
+    let parse = |s: &str| -> Option<[usize; 3]> {
+        let (x, y, z) = s.split(',').map(|n| n.parse().ok()).collect_tuple()?;
+        Some([x?, y?, z?])
+    };
+    let (a, b) = (parse(a)?, parse(b)?);
+    Some(Brick {
+        min: [0, 1, 2].map(|i| a[i].min(b[i])),
+        max: [0, 1, 2].map(|i| a[i].max(b[i])),
+    })
+}
+
+/// Settled bricks in the order they came to rest, so every brick comes after the ones
+/// holding it up.
+struct Stack {
+    /// Bricks directly below and touching each brick.
+    supported_by: Vec<Vec<usize>>,
+    /// Bricks directly above and touching each brick.
+    supports: Vec<Vec<usize>>,
+}
+
+impl Stack {
+    fn settle(mut bricks: Vec<Brick>) -> Self {
+        bricks.sort_unstable_by_key(|b| b.min[2]);
+        let width = bricks.iter().map(|b| b.max[0] + 1).max().unwrap_or(0);
+        let depth = bricks.iter().map(|b| b.max[1] + 1).max().unwrap_or(0);
+        // height of the highest settled cube in each column, along with the brick it belongs to
+        let mut top = vec![(0, None::<usize>); width * depth];
+
+        let mut supported_by = vec![vec![]; bricks.len()];
+        let mut supports = vec![vec![]; bricks.len()];
+        for (i, brick) in bricks.iter().enumerate() {
+            let columns = (brick.min[1]..=brick.max[1])
+                .flat_map(|y| (brick.min[0]..=brick.max[0]).map(move |x| y * width + x))
+                .collect_vec();
+            let floor = columns.iter().map(|&c| top[c].0).max().unwrap_or(0);
+            for &c in &columns {
+                if let (height, Some(below)) = top[c] {
+                    if height == floor && !supported_by[i].contains(&below) {
+                        supported_by[i].push(below);
+                        supports[below].push(i);
+                    }
+                }
+                top[c] = (floor + 1 + brick.max[2] - brick.min[2], Some(i));
+            }
+        }
+        Stack {
+            supported_by,
+            supports,
+        }
+    }
+
+    /// Whether removing `brick` leaves everything it holds up still supported.
+    fn can_disintegrate(&self, brick: usize) -> bool {
+        self.supports[brick]
+            .iter()
+            .all(|&above| self.supported_by[above].len() > 1)
+    }
+
+    /// Number of other bricks that fall when `brick` is removed.
+    fn chain_reaction(&self, brick: usize) -> usize {
+        let mut falling = vec![false; self.supports.len()];
+        falling[brick] = true;
+        let mut count = 0;
+        for i in brick + 1..falling.len() {
+            let supported_by = &self.supported_by[i];
+            if !supported_by.is_empty() && supported_by.iter().all(|&b| falling[b]) {
+                falling[i] = true;
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+fn settle(input: impl Iterator<Item = String>) -> Stack {
+    Stack::settle(input.filter_map(|line| parse_brick(&line)).collect())
+}
+
+#[aoc(part = 1, example = 5)]
+pub fn part1(input: impl Iterator<Item = String>) -> usize {
+    let stack = settle(input);
+    (0..stack.supports.len())
+        .filter(|&b| stack.can_disintegrate(b))
+        .count()
+}
+
+#[aoc(part = 2, example = 7)]
+pub fn part2(input: impl Iterator<Item = String>) -> usize {
+    let stack = settle(input);
+    (0..stack.supports.len())
+        .map(|b| stack.chain_reaction(b))
+        .sum()
+}
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use aoc_framework::{
+    anyhow::Context,
+    direction::Direction,
+    grid::{Grid, GridView},
+    point::{Point, Point2},
+    *,
+};
+
+pub struct Day23;
+
+impl_day_with_examples!(Day23::{part1, part2}: 2023[23], r"
+#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#
+");
+
+/// Direction a slope forces.
+fn slope_dir(b: u8) -> Option<Direction<2>> {
+    match b {
+        b'>' => Some(Direction::EAST),
+        b'^' => Some(Direction::NORTH),
+        b'<' => Some(Direction::WEST),
+        b'v' => Some(Direction::SOUTH),
+        _ => None,
+    }
+}
+
+fn is_path(g: &GridView<'_, u8, 2>, p: Point2) -> bool {
+    g.get(p).is_some_and(|&b| b != b'#')
+}
+
+/// Junctions of the trail map with the corridors between them collapsed into weighted edges.
+/// Node 0 is the start and node 1 the end.
+struct TrailGraph {
+    /// `(to, length)` for every corridor leaving each junction.
+    edges: Vec<Vec<(usize, u64)>>,
+}
+
+impl TrailGraph {
+    fn parse(input: Vec<u8>, slippery: bool) -> anyhow::Result<Self> {
+        let g = Grid::from_bytes(input);
+        let Point([w, h]) = g.size();
+        let open_in_row = |y| {
+            (0..w)
+                .map(move |x| Point2::new(x, y))
+                .find(|&p| is_path(&g, p))
+        };
+        let (Some(start), Some(end)) = (open_in_row(0), open_in_row(h - 1)) else {
+            anyhow::bail!("no opening in the first or last row");
+        };
+
+        let mut junctions = vec![start, end];
+        junctions.extend(g.points_iter().filter(|&p| {
+            is_path(&g, p)
+                && (0..4)
+                    .filter(|&i| is_path(&g, p + Direction::new(i)))
+                    .count()
+                    > 2
+        }));
+        anyhow::ensure!(
+            junctions.len() <= 64,
+            "{} junctions don't fit in the visited mask",
+            junctions.len()
+        );
+        let index: HashMap<_, _> = junctions
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.0, i))
+            .collect();
+
+        let mut edges = vec![vec![]; junctions.len()];
+        for (from, &junction) in junctions.iter().enumerate() {
+            'corridor: for start_dir in [
+                Direction::EAST,
+                Direction::NORTH,
+                Direction::WEST,
+                Direction::SOUTH,
+            ] {
+                let (mut dir, mut pos, mut len) = (start_dir, junction, 0);
+                loop {
+                    let next = pos + dir;
+                    if !is_path(&g, next) {
+                        continue 'corridor;
+                    }
+                    if slippery && slope_dir(g[next]).is_some_and(|s| s != dir) {
+                        continue 'corridor;
+                    }
+                    pos = next;
+                    len += 1;
+                    if let Some(&to) = index.get(&pos.0) {
+                        edges[from].push((to, len));
+                        continue 'corridor;
+                    }
+                    // follow the corridor without turning back, dead ends are dropped; a slope
+                    // keeps going the way it points
+                    let on_slope = slippery && slope_dir(g[pos]).is_some();
+                    let Some(turn) = [dir, dir + 1, dir - 1]
+                        .into_iter()
+                        .take(if on_slope { 1 } else { 3 })
+                        .find(|&d| is_path(&g, pos + d))
+                    else {
+                        continue 'corridor;
+                    };
+                    dir = turn;
+                }
+            }
+        }
+        Ok(TrailGraph { edges })
+    }
+
+    /// Length of the longest path from the start to the end that visits no junction twice.
+    fn longest_hike(&self) -> Option<u64> {
+        // the last junction before the end has to go straight there, or it would be cut off
+        let into_end = self
+            .edges
+            .iter()
+            .enumerate()
+            .flat_map(|(j, e)| {
+                e.iter()
+                    .filter(|&&(to, _)| to == 1)
+                    .map(move |&(_, len)| (j, len))
+            })
+            .collect_vec();
+        let (target, tail) = match into_end[..] {
+            [only] => only,
+            _ => (1, 0),
+        };
+        self.search(0, target, 1).map(|len| len + tail)
+    }
+
+    fn search(&self, node: usize, target: usize, visited: u64) -> Option<u64> {
+        if node == target {
+            return Some(0);
+        }
+        self.edges[node]
+            .iter()
+            .filter(|&&(to, _)| visited & 1 << to == 0)
+            .filter_map(|&(to, len)| Some(len + self.search(to, target, visited | 1 << to)?))
+            .max()
+    }
+}
+
+fn longest_hike(input: Vec<u8>, slippery: bool) -> anyhow::Result<u64> {
+    TrailGraph::parse(input, slippery)?
+        .longest_hike()
+        .context("no path from the start to the end")
+}
+
+#[aoc(part = 1, example = 94)]
+pub fn part1(input: Vec<u8>) -> anyhow::Result<u64> {
+    longest_hike(input, true)
+}
+
+#[aoc(part = 2, example = 154)]
+pub fn part2(input: Vec<u8>) -> anyhow::Result<u64> {
+    longest_hike(input, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a single corridor of 30 steps, going down each kind of slope the way it points
+    const ALL_SLOPES: &str = "\
+#.#######
+#.>.....#
+#######v#
+#...<...#
+#.#######
+#.#...###
+#.#^#.###
+#...#...#
+#######.#
+";
+
+    #[test]
+    fn slopes_in_every_direction() {
+        assert_eq!(part1(ALL_SLOPES.as_bytes().to_vec()).unwrap(), 30);
+        assert_eq!(part2(ALL_SLOPES.as_bytes().to_vec()).unwrap(), 30);
+    }
+
+    #[test]
+    fn slopes_are_one_way() {
+        for (slope, reversed) in [('>', "<"), ('v', "^"), ('<', ">"), ('^', "v")] {
+            let map = ALL_SLOPES.replace(slope, reversed);
+            assert!(part1(map.as_bytes().to_vec()).is_err(), "{slope} reversed");
+            assert_eq!(part2(map.into_bytes()).unwrap(), 30);
+        }
+    }
+
+    #[test]
+    fn no_turning_on_a_slope() {
+        // the slope points into a wall, so only the dry path can turn south after it
+        let map = "\
+#.###
+#.>##
+##.##
+##.##
+";
+        assert!(part1(map.as_bytes().to_vec()).is_err());
+        assert_eq!(part2(map.as_bytes().to_vec()).unwrap(), 4);
+    }
+}
@@ -0,0 +1,171 @@
+use aoc_framework::*;
+
+pub struct Day24;
+
+impl_day_with_examples!(Day24::{part1, part2}: 2023[24], r"
+19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3
+");
+
+const TEST_AREA: (i128, i128) = (200000000000000, 400000000000000);
+
+type Vec3 = [i128; 3];
+
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn dot(a: Vec3, b: Vec3) -> i128 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[derive(Clone, Copy)]
+struct Hailstone {
+    p: Vec3,
+    v: Vec3,
+}
+
+impl Hailstone {
+    fn at(&self, t: i128) -> Vec3 {
+        [0, 1, 2].map(|i| self.p[i] + t * self.v[i])
+    }
+
+    /// Whether the future X/Y paths of both hailstones cross inside `min..=max` on both axes.
+    fn crosses_within(&self, other: &Hailstone, (min, max): (i128, i128)) -> bool {
+        let [dx, dy, _] = sub(other.p, self.p);
+        let ([ax, ay, _], [bx, by, _]) = (self.v, other.v);
+        // solve self.p + t * self.v = other.p + s * other.v with Cramer's rule,
+        // keeping t and s as fractions over a positive denominator
+        let mut det = bx * ay - ax * by;
+        let (mut t, mut s) = (bx * dy - dx * by, ax * dy - dx * ay);
+        if det == 0 {
+            return false;
+        }
+        if det < 0 {
+            (det, t, s) = (-det, -t, -s);
+        }
+        if t < 0 || s < 0 {
+            return false;
+        }
+        let in_area = |p: i128, v: i128| (min * det..=max * det).contains(&(p * det + t * v));
+        in_area(self.p[0], ax) && in_area(self.p[1], ay)
+    }
+}
+
+fn parse_hailstone(line: &str) -> Option<Hailstone> {
+    let (p, v) = line.split_once('@')?;
+    let parse = |s: &str| -> Option<Vec3> {
+        let (x, y, z) = s
+            .split(',')
+            .map(|n| n.trim().parse().ok())
+            .collect_tuple()?;
+        Some([x?, y?, z?])
+    };
+    Some(Hailstone {
+        p: parse(p)?,
+        v: parse(v)?,
+    })
+}
+
+fn parse(input: &str) -> Vec<Hailstone> {
+    input.lines().filter_map(parse_hailstone).collect()
+}
+
+fn count_crossings(hail: &[Hailstone], area: (i128, i128)) -> usize {
+    hail.iter()
+        .tuple_combinations()
+        .filter(|(a, b)| a.crosses_within(b, area))
+        .count()
+}
+
+/// Collision time of hailstone `b` with a line through the origin lying in the plane with
+/// normal `n`, in the frame where the reference hailstone stays at the origin.
+fn time_in_plane(n: Vec3, b: &Hailstone) -> Option<i128> {
+    let den = dot(n, b.v);
+    let num = -dot(n, b.p);
+    (den != 0 && num % den == 0).then(|| num / den)
+}
+
+/// The starting position and velocity of a rock that hits every hailstone.
+///
+/// Seen from the first hailstone, the rock's line goes through the origin, so it lies in the
+/// plane through the origin and any other hailstone's path. Intersecting one hailstone's path
+/// with another's plane gives both collision times, and the rock follows from those.
+fn find_rock(hail: &[Hailstone]) -> anyhow::Result<Hailstone> {
+    let Some((h0, rest)) = hail.split_first() else {
+        anyhow::bail!("no hailstones");
+    };
+    let relative = |h: &Hailstone| Hailstone {
+        p: sub(h.p, h0.p),
+        v: sub(h.v, h0.v),
+    };
+    for (a, b) in rest.iter().tuple_combinations() {
+        let (ra, rb) = (relative(a), relative(b));
+        let (na, nb) = (cross(ra.p, ra.v), cross(rb.p, rb.v));
+        let (Some(ta), Some(tb)) = (time_in_plane(nb, &ra), time_in_plane(na, &rb)) else {
+            continue;
+        };
+        if ta == tb {
+            continue;
+        }
+        let dist = sub(b.at(tb), a.at(ta));
+        if dist.iter().any(|d| d % (tb - ta) != 0) {
+            continue;
+        }
+        let v = dist.map(|d| d / (tb - ta));
+        let rock = Hailstone {
+            p: sub(a.at(ta), v.map(|c| c * ta)),
+            v,
+        };
+        let hits = |h: &Hailstone| cross(sub(rock.p, h.p), sub(rock.v, h.v)) == [0; 3];
+        anyhow::ensure!(
+            hail.iter().all(hits),
+            "the rock through hailstones {:?} and {:?} misses others",
+            a.p,
+            b.p
+        );
+        return Ok(rock);
+    }
+    anyhow::bail!("no pair of hailstones pins down an integer rock trajectory")
+}
+
+/// Counts the crossing paths inside the given test area.
+pub fn crossings(input: &str, min: i128, max: i128) -> usize {
+    count_crossings(&parse(input), (min, max))
+}
+
+/// Describes the rock's trajectory and when it hits each hailstone.
+pub fn describe_rock(input: &str) -> anyhow::Result<String> {
+    let hail = parse(input);
+    let rock = find_rock(&hail)?;
+    let mut out = format!("rock {:?} @ {:?}\n", rock.p, rock.v);
+    for h in &hail {
+        // the rock and the hailstone meet where their positions match on any moving axis
+        let t = (0..3)
+            .find(|&i| rock.v[i] != h.v[i])
+            .map_or(0, |i| (h.p[i] - rock.p[i]) / (rock.v[i] - h.v[i]));
+        out += &format!("hits {:?} @ {:?} at t = {t}, {:?}\n", h.p, h.v, h.at(t));
+    }
+    Ok(out)
+}
+
+#[aoc(part = 1)]
+pub fn part1(input: &str) -> usize {
+    count_crossings(&parse(input), TEST_AREA)
+}
+
+#[aoc(part = 2, example = 47)]
+pub fn part2(input: &str) -> anyhow::Result<i128> {
+    Ok(find_rock(&parse(input))?.p.iter().sum())
+}
@@ -0,0 +1,143 @@
+use std::collections::{HashMap, VecDeque};
+
+use aoc_framework::*;
+
+pub struct Day25;
+
+impl_day_with_examples!(Day25::{part1}: 2023[25], r"
+jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr
+");
+
+const CUT_SIZE: usize = 3;
+
+/// Undirected wiring diagram, every wire can carry one unit of flow.
+struct Graph {
+    names: Vec<String>,
+    edges: Vec<(usize, usize)>,
+    /// `(neighbour, edge)` for each component.
+    adj: Vec<Vec<(usize, usize)>>,
+}
+
+impl Graph {
+    fn parse(input: &str) -> Self {
+        let mut graph = Graph {
+            names: vec![],
+            edges: vec![],
+            adj: vec![],
+        };
+        let mut ids = HashMap::new();
+        let mut id = |graph: &mut Graph, name: &str| {
+            *ids.entry(name.to_string()).or_insert_with(|| {
+                graph.names.push(name.to_string());
+                graph.adj.push(vec![]);
+                graph.names.len() - 1
+            })
+        };
+        for line in input.lines() {
+            let Some((from, to)) = line.split_once(':') else {
+                continue;
+            };
+            let from = id(&mut graph, from.trim());
+            for to in to.split_whitespace() {
+                let to = id(&mut graph, to);
+                graph.adj[from].push((to, graph.edges.len()));
+                graph.adj[to].push((from, graph.edges.len()));
+                graph.edges.push((from, to));
+            }
+        }
+        graph
+    }
+
+    /// Searches the residual graph from `source`, returning the edge each component was
+    /// first reached through.
+    fn residual_bfs(&self, source: usize, flow: &[i8]) -> Vec<Option<Option<usize>>> {
+        let mut reached_by = vec![None; self.adj.len()];
+        reached_by[source] = Some(None);
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            for &(next, e) in &self.adj[node] {
+                // flow is measured from the edge's first endpoint to its second
+                let forward = if self.edges[e].0 == node { 1 } else { -1 };
+                if reached_by[next].is_none() && flow[e] * forward < 1 {
+                    reached_by[next] = Some(Some(e));
+                    queue.push_back(next);
+                }
+            }
+        }
+        reached_by
+    }
+
+    /// Components on the source side of a cut of at most `max_cut` wires separating `source`
+    /// from `sink`, if there is one (Ford-Fulkerson with unit capacities).
+    fn min_cut(&self, source: usize, sink: usize, max_cut: usize) -> Option<Vec<bool>> {
+        let mut flow = vec![0i8; self.edges.len()];
+        for _ in 0..=max_cut {
+            let reached_by = self.residual_bfs(source, &flow);
+            if reached_by[sink].is_none() {
+                return Some(reached_by.iter().map(Option::is_some).collect());
+            }
+            let mut node = sink;
+            while let Some(Some(e)) = reached_by[node] {
+                let (a, b) = self.edges[e];
+                (flow[e], node) = if b == node {
+                    (flow[e] + 1, a)
+                } else {
+                    (flow[e] - 1, b)
+                };
+            }
+        }
+        None
+    }
+
+    /// Splits the components in two by cutting exactly `size` wires.
+    fn split(&self, size: usize) -> anyhow::Result<Vec<bool>> {
+        // any component on the other side of the cut from component 0 will do
+        for sink in 1..self.adj.len() {
+            if let Some(side) = self.min_cut(0, sink, size) {
+                let cut = self.cut_edges(&side).count();
+                anyhow::ensure!(cut == size, "found a cut of only {cut} wires");
+                return Ok(side);
+            }
+        }
+        anyhow::bail!("no cut of {size} wires splits the diagram")
+    }
+
+    fn cut_edges<'a>(&'a self, side: &'a [bool]) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.edges
+            .iter()
+            .copied()
+            .filter(|&(a, b)| side[a] != side[b])
+    }
+}
+
+/// Lists the wires to cut along with the sizes of the resulting groups.
+pub fn describe_cut(input: &str) -> anyhow::Result<String> {
+    let graph = Graph::parse(input);
+    let side = graph.split(CUT_SIZE)?;
+    let mut out = graph
+        .cut_edges(&side)
+        .map(|(a, b)| format!("{}/{}\n", graph.names[a], graph.names[b]))
+        .collect::<String>();
+    let left = side.iter().filter(|&&s| s).count();
+    out += &format!("groups of {left} and {}\n", side.len() - left);
+    Ok(out)
+}
+
+#[aoc(part = 1, example = 54)]
+pub fn part1(input: &str) -> anyhow::Result<usize> {
+    let side = Graph::parse(input).split(CUT_SIZE)?;
+    let left = side.iter().filter(|&&s| s).count();
+    Ok(left * (side.len() - left))
+}
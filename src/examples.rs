@@ -0,0 +1,187 @@
+use crate::*;
+
+/// A puzzle example along with every answer known for it.
+pub struct Example {
+    pub day: usize,
+    /// Usually one of the day's own `EXAMPLES`, the leading newline is dropped.
+    pub input: &'static str,
+    /// `(part, parameters, expected)`, the parameters replace the puzzle's own constants where
+    /// the example uses different ones.
+    pub checks: &'static [(u8, &'static [u64], u64)],
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        day: 1,
+        input: day01::EXAMPLES[0],
+        checks: &[(1, &[], 142)],
+    },
+    Example {
+        day: 1,
+        input: day01::EXAMPLES[1],
+        checks: &[(2, &[], 281)],
+    },
+    Example {
+        day: 2,
+        input: day02::EXAMPLES[0],
+        checks: &[(1, &[], 8), (2, &[], 2286)],
+    },
+    Example {
+        day: 3,
+        input: day03::EXAMPLES[0],
+        checks: &[(1, &[], 4361), (2, &[], 467835)],
+    },
+    Example {
+        day: 4,
+        input: day04::EXAMPLES[0],
+        checks: &[(1, &[], 13), (2, &[], 30)],
+    },
+    Example {
+        day: 5,
+        input: day05::EXAMPLES[0],
+        checks: &[(1, &[], 35), (2, &[], 46)],
+    },
+    Example {
+        day: 6,
+        input: day06::EXAMPLES[0],
+        checks: &[(1, &[], 288), (2, &[], 71503)],
+    },
+    Example {
+        day: 7,
+        input: day07::EXAMPLES[0],
+        checks: &[(1, &[], 6440), (2, &[], 5905)],
+    },
+    Example {
+        day: 8,
+        input: day08::EXAMPLES[0],
+        checks: &[(1, &[], 2)],
+    },
+    Example {
+        day: 8,
+        input: day08::EXAMPLES[1],
+        checks: &[(2, &[], 6)],
+    },
+    Example {
+        day: 8,
+        input: day08::EXAMPLES[2],
+        checks: &[(1, &[], 6)],
+    },
+    Example {
+        day: 9,
+        input: day09::EXAMPLES[0],
+        checks: &[(1, &[], 114), (2, &[], 2)],
+    },
+    Example {
+        day: 10,
+        input: day10::EXAMPLES[0],
+        checks: &[(1, &[], 8)],
+    },
+    Example {
+        day: 10,
+        input: day10::EXAMPLES[1],
+        checks: &[(2, &[], 10)],
+    },
+    Example {
+        day: 11,
+        input: day11::EXAMPLES[0],
+        checks: &[
+            (1, &[], 374),
+            (2, &[], 82000210),
+            (2, &[10], 1030),
+            (2, &[100], 8410),
+        ],
+    },
+    Example {
+        day: 12,
+        input: day12::EXAMPLES[0],
+        checks: &[(1, &[], 21), (2, &[], 525152)],
+    },
+    Example {
+        day: 13,
+        input: day13::EXAMPLES[0],
+        checks: &[(1, &[], 405), (2, &[], 400)],
+    },
+    Example {
+        day: 14,
+        input: day14::EXAMPLES[0],
+        checks: &[(1, &[], 136), (2, &[], 64)],
+    },
+    Example {
+        day: 15,
+        input: day15::EXAMPLES[0],
+        checks: &[(1, &[], 1320), (2, &[], 145)],
+    },
+    Example {
+        day: 16,
+        input: day16::EXAMPLES[0],
+        checks: &[(1, &[], 46), (2, &[], 51)],
+    },
+    Example {
+        day: 17,
+        input: day17::EXAMPLES[0],
+        checks: &[(1, &[], 102), (2, &[], 94)],
+    },
+    Example {
+        day: 17,
+        input: day17::EXAMPLES[1],
+        checks: &[(2, &[], 71)],
+    },
+    Example {
+        day: 18,
+        input: day18::EXAMPLES[0],
+        checks: &[(1, &[], 62), (2, &[], 952408144115)],
+    },
+    Example {
+        day: 19,
+        input: day19::EXAMPLES[0],
+        checks: &[(1, &[], 19114), (2, &[], 167409079868000)],
+    },
+    Example {
+        day: 20,
+        input: day20::EXAMPLES[0],
+        checks: &[(1, &[], 11687500)],
+    },
+    Example {
+        day: 20,
+        input: day20::EXAMPLES[1],
+        checks: &[(1, &[], 163651095), (2, &[], 15)],
+    },
+    Example {
+        day: 20,
+        input: day20::EXAMPLES[2],
+        checks: &[(1, &[], 32000000)],
+    },
+    Example {
+        day: 21,
+        input: day21::EXAMPLES[0],
+        checks: &[
+            (1, &[6], 16),
+            (2, &[6], 16),
+            (2, &[10], 50),
+            (2, &[50], 1594),
+            (2, &[100], 6536),
+            (2, &[500], 167004),
+            (2, &[1000], 668697),
+        ],
+    },
+    Example {
+        day: 22,
+        input: day22::EXAMPLES[0],
+        checks: &[(1, &[], 5), (2, &[], 7)],
+    },
+    Example {
+        day: 23,
+        input: day23::EXAMPLES[0],
+        checks: &[(1, &[], 94), (2, &[], 154)],
+    },
+    Example {
+        day: 24,
+        input: day24::EXAMPLES[0],
+        checks: &[(1, &[7, 27], 2), (2, &[], 47)],
+    },
+    Example {
+        day: 25,
+        input: day25::EXAMPLES[0],
+        checks: &[(1, &[], 54)],
+    },
+];
@@ -0,0 +1,131 @@
+use aoc_framework::{
+    direction::Direction,
+    point::{Point, Point2},
+    *,
+};
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b > 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Closed polygon whose vertices all lie on integer coordinates.
+pub struct Polygon {
+    vertices: Vec<Point2>,
+}
+
+impl Polygon {
+    /// The polygon is closed implicitly, the last vertex connects back to the first.
+    pub fn from_vertices(vertices: Vec<Point2>) -> Self {
+        Polygon { vertices }
+    }
+
+    /// Follows the segments starting from the origin.
+    pub fn from_segments(segments: impl IntoIterator<Item = (Direction<2>, isize)>) -> Self {
+        let mut current = Point::default();
+        let vertices = segments
+            .into_iter()
+            .map(|(dir, len)| {
+                current += dir.delta() * len;
+                current
+            })
+            .collect();
+        Polygon { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point2] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (&Point2, &Point2)> {
+        self.vertices
+            .iter()
+            .chain(self.vertices.first())
+            .tuple_windows()
+    }
+
+    /// Twice the area, which is always an integer for lattice polygons (shoelace formula).
+    pub fn double_area(&self) -> u64 {
+        self.edges()
+            .map(|(l, r)| l.x() * r.y() - r.x() * l.y())
+            .sum::<isize>()
+            .unsigned_abs() as u64
+    }
+
+    /// Number of lattice points lying on the edges.
+    pub fn boundary_points(&self) -> u64 {
+        self.edges()
+            .map(|(l, r)| {
+                let Point([dx, dy]) = *r - *l;
+                gcd(dx.unsigned_abs() as u64, dy.unsigned_abs() as u64)
+            })
+            .sum()
+    }
+
+    /// Number of lattice points strictly inside, from Pick's theorem (A = i + b/2 - 1).
+    pub fn interior_points(&self) -> u64 {
+        (self.double_area() + 2).saturating_sub(self.boundary_points()) / 2
+    }
+
+    /// Number of lattice points inside or on the boundary.
+    pub fn enclosed_points(&self) -> u64 {
+        self.interior_points() + self.boundary_points()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(vertices: &[(isize, isize)]) -> Polygon {
+        Polygon::from_vertices(vertices.iter().map(|&(x, y)| Point([x, y])).collect())
+    }
+
+    fn counts(p: &Polygon) -> [u64; 4] {
+        [
+            p.double_area(),
+            p.boundary_points(),
+            p.interior_points(),
+            p.enclosed_points(),
+        ]
+    }
+
+    #[test]
+    fn unit_square() {
+        assert_eq!(
+            counts(&polygon(&[(0, 0), (1, 0), (1, 1), (0, 1)])),
+            [2, 4, 0, 4]
+        );
+    }
+
+    #[test]
+    fn concave_l_shape() {
+        let l = [(0, 0), (4, 0), (4, 2), (2, 2), (2, 4), (0, 4)];
+        // 15 points in the bottom 5x3 block and 6 more in the 3x2 arm above it
+        assert_eq!(counts(&polygon(&l)), [24, 16, 5, 21]);
+    }
+
+    #[test]
+    fn orientation_doesnt_matter() {
+        let mut triangle = vec![(0, 0), (4, 0), (0, 4)];
+        // counter-clockwise with y pointing up, the inside is (1, 1), (1, 2) and (2, 1)
+        assert_eq!(counts(&polygon(&triangle)), [16, 12, 3, 15]);
+        triangle.reverse();
+        assert_eq!(counts(&polygon(&triangle)), [16, 12, 3, 15]);
+    }
+
+    #[test]
+    fn from_segments() {
+        let segments = [
+            (Direction::EAST, 3),
+            (Direction::SOUTH, 2),
+            (Direction::WEST, 3),
+            (Direction::NORTH, 2),
+        ];
+        let rect = Polygon::from_segments(segments);
+        assert_eq!(rect.vertices().len(), 4);
+        assert_eq!(counts(&rect), [12, 10, 2, 12]);
+    }
+}
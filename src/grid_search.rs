@@ -0,0 +1,190 @@
+use std::{collections::VecDeque, mem};
+
+use aoc_framework::{
+    direction::Direction,
+    grid::{Grid, GridView},
+    point::{Point, Point2},
+};
+use fxhash::FxHashSet;
+
+/// Number of steps needed to reach each cell, `None` for unreachable cells.
+pub type DistanceMap = GridView<'static, Option<u32>, 2>;
+
+/// Breadth-first search from all `sources` at once, moving orthogonally between passable cells.
+pub fn bfs<T: Clone>(
+    g: &GridView<'_, T, 2>,
+    sources: impl IntoIterator<Item = Point2>,
+    passable: impl Fn(&T) -> bool,
+) -> DistanceMap {
+    let Point([w, h]) = g.size();
+    let mut dist: DistanceMap = Grid::from_data(vec![None; (w * h) as usize], w as usize);
+    let mut queue = VecDeque::new();
+    for source in sources {
+        if dist.get(source) == Some(&None) && g.get(source).is_some_and(&passable) {
+            dist.set(source, Some(0));
+            queue.push_back(source);
+        }
+    }
+    while let Some(pos) = queue.pop_front() {
+        let next_dist = dist[pos].map(|d| d + 1);
+        for i in 0..4 {
+            let neighbor = pos + Direction::new(i);
+            if dist.get(neighbor) == Some(&None) && g.get(neighbor).is_some_and(&passable) {
+                dist.set(neighbor, next_dist);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    dist
+}
+
+/// Breadth-first search on `g` repeated infinitely in every direction, returning how many cells
+/// are first reached after each number of steps up to `max_steps`. Neighbouring cells are never
+/// more than one step apart, so only the last two layers are kept and memory grows with the
+/// frontier rather than with the area covered.
+pub fn tiled_layer_sizes<T: Clone>(
+    g: &GridView<'_, T, 2>,
+    sources: impl IntoIterator<Item = Point2>,
+    passable: impl Fn(&T) -> bool,
+    max_steps: u64,
+) -> Vec<u64> {
+    let Point([w, h]) = g.size();
+    let open = |p: Point2| passable(&g[Point([p.x().rem_euclid(w), p.y().rem_euclid(h)])]);
+    let mut previous = FxHashSet::default();
+    let mut current = sources
+        .into_iter()
+        .filter(|&p| open(p))
+        .map(|p| p.0)
+        .collect::<FxHashSet<_>>();
+    let mut sizes = vec![current.len() as u64];
+    for _ in 0..max_steps {
+        let next = current
+            .iter()
+            .flat_map(|&p| (0..4).map(move |i| Point(p) + Direction::new(i)))
+            .filter(|n| !previous.contains(&n.0) && !current.contains(&n.0) && open(*n))
+            .map(|p| p.0)
+            .collect::<FxHashSet<_>>();
+        sizes.push(next.len() as u64);
+        previous = mem::replace(&mut current, next);
+    }
+    sizes
+}
+
+/// Every cell connected to `start`, including itself if it is passable.
+pub fn flood_fill<T: Clone>(
+    g: &GridView<'_, T, 2>,
+    start: Point2,
+    passable: impl Fn(&T) -> bool,
+) -> Vec<Point2> {
+    let dist = bfs(g, [start], passable);
+    dist.points_iter().filter(|&p| dist[p].is_some()).collect()
+}
+
+/// Labels passable cells with the index of their connected component, and returns the number
+/// of components.
+pub fn components<T: Clone>(
+    g: &GridView<'_, T, 2>,
+    passable: impl Fn(&T) -> bool,
+) -> (GridView<'static, Option<u32>, 2>, u32) {
+    let Point([w, h]) = g.size();
+    let mut labels = Grid::from_data(vec![None; (w * h) as usize], w as usize);
+    let mut count = 0;
+    for start in g.points_iter() {
+        if labels[start].is_some() || !passable(&g[start]) {
+            continue;
+        }
+        labels.set(start, Some(count));
+        let mut stack = vec![start];
+        while let Some(pos) = stack.pop() {
+            for i in 0..4 {
+                let neighbor = pos + Direction::new(i);
+                if labels.get(neighbor) == Some(&None) && g.get(neighbor).is_some_and(&passable) {
+                    labels.set(neighbor, Some(count));
+                    stack.push(neighbor);
+                }
+            }
+        }
+        count += 1;
+    }
+    (labels, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_framework::Itertools;
+
+    use super::*;
+
+    fn grid(rows: &str) -> GridView<'static, u8, 2> {
+        Grid::from_bytes(rows.as_bytes().to_vec())
+    }
+
+    fn open(b: &u8) -> bool {
+        *b != b'#'
+    }
+
+    #[test]
+    fn distances() {
+        let g = grid("..#..\n.##..\n.....\n");
+        let dist = bfs(&g, [Point([0, 0])], open);
+        assert_eq!(dist[(0, 2)], Some(2));
+        assert_eq!(dist[(3, 1)], Some(6));
+        assert_eq!(dist[(4, 0)], Some(8));
+        assert_eq!(dist[(2, 0)], None);
+    }
+
+    #[test]
+    fn multiple_sources() {
+        let g = grid("..#..\n.##..\n.....\n");
+        let dist = bfs(&g, [Point([0, 0]), Point([4, 0])], open);
+        assert_eq!(dist[(4, 0)], Some(0));
+        assert_eq!(dist[(3, 2)], Some(3));
+        assert_eq!(dist[(2, 2)], Some(4));
+        // sources on walls or outside the grid are ignored
+        let dist = bfs(&g, [Point([2, 0]), Point([9, 9])], open);
+        assert!(dist.data().iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn fill() {
+        let g = grid("..#..\n..#..\n");
+        let mut cells = flood_fill(&g, Point([1, 1]), open)
+            .iter()
+            .map(|p| p.0)
+            .collect_vec();
+        cells.sort_unstable();
+        assert_eq!(cells, [[0, 0], [0, 1], [1, 0], [1, 1]]);
+        assert!(flood_fill(&g, Point([2, 0]), open).is_empty());
+    }
+
+    #[test]
+    fn labelled_components() {
+        let g = grid(".#.\n##.\n..#\n");
+        let (labels, count) = components(&g, open);
+        assert_eq!(count, 3);
+        assert_eq!(labels[(2, 0)], labels[(2, 1)]);
+        assert_eq!(labels[(0, 2)], labels[(1, 2)]);
+        assert_ne!(labels[(0, 0)], labels[(2, 0)]);
+        assert_ne!(labels[(0, 0)], labels[(0, 2)]);
+        assert_eq!(labels[(1, 1)], None);
+    }
+
+    #[test]
+    fn tiled_layers() {
+        // an open plane reaches a diamond whose edge grows by 4 cells per step
+        assert_eq!(
+            tiled_layer_sizes(&grid(".\n"), [Point([0, 0])], open, 3),
+            [1, 4, 8, 12]
+        );
+
+        // same as a plain search on enough copies of the grid
+        let tile = ["...", ".#.", "..."];
+        let copies = (0..9).map(|y| tile[y % 3].repeat(3)).join("\n") + "\n";
+        let dist = bfs(&grid(&copies), [Point([3, 3])], open);
+        let expected = (0..=3)
+            .map(|d| dist.data().iter().filter(|&&n| n == Some(d)).count() as u64)
+            .collect_vec();
+        let layers = tiled_layer_sizes(&grid(&(tile.join("\n") + "\n")), [Point([0, 0])], open, 3);
+        assert_eq!(layers, expected);
+    }
+}
@@ -0,0 +1,222 @@
+use aoc_framework::*;
+
+mod answer;
+pub mod examples;
+pub mod geometry;
+pub mod grid_search;
+pub mod pathfinding;
+pub mod profile;
+pub mod solution;
+
+pub use answer::Answer;
+use solution::{run_solution, Phase};
+
+/// `impl_day!` that also keeps the examples in a `pub const EXAMPLES`, so `examples::EXAMPLES`
+/// can refer to them instead of keeping its own copy.
+macro_rules! impl_day_with_examples {
+    ($day:ident::{$($part:ident),*}: $year:tt[$n:tt] $(, $example:tt)* $(,)?) => {
+        impl_day!($day::{$($part),*}: $year[$n] $(, $example)*);
+
+        pub const EXAMPLES: &[&str] = &[$($example),*];
+    };
+}
+
+pub mod day01;
+pub use day01::Day01;
+
+pub mod day02;
+pub use day02::Day02;
+
+pub mod day03;
+pub use day03::Day03;
+
+pub mod day04;
+pub use day04::Day04;
+
+pub mod day05;
+pub use day05::Day05;
+
+pub mod day06;
+pub use day06::Day06;
+
+pub mod day07;
+pub use day07::Day07;
+
+pub mod day08;
+pub use day08::Day08;
+
+pub mod day09;
+pub use day09::Day09;
+
+pub mod day10;
+pub use day10::Day10;
+
+pub mod day11;
+pub use day11::Day11;
+
+pub mod day12;
+pub use day12::Day12;
+
+pub mod day13;
+pub use day13::Day13;
+
+pub mod day14;
+pub use day14::Day14;
+
+pub mod day15;
+pub use day15::Day15;
+
+pub mod day16;
+pub use day16::Day16;
+
+pub mod day17;
+pub use day17::Day17;
+
+pub mod day18;
+pub use day18::Day18;
+
+pub mod day19;
+pub use day19::Day19;
+
+pub mod day20;
+pub use day20::Day20;
+
+pub mod day21;
+pub use day21::Day21;
+
+pub mod day22;
+pub use day22::Day22;
+
+pub mod day23;
+pub use day23::Day23;
+
+pub mod day24;
+pub use day24::Day24;
+
+pub mod day25;
+pub use day25::Day25;
+
+/// Runs one part on `input`, passing it in whatever form the part function takes. `run_day`
+/// solves every part with a single parse instead.
+pub fn solve(day: usize, part: u8, input: &str) -> anyhow::Result<Answer> {
+    let lines = || input.lines().map(String::from);
+    let bytes = || input.as_bytes().to_vec();
+    Ok(match (day, part) {
+        (1, 1) => day01::part1(lines()).into(),
+        (1, 2) => day01::part2(lines()).into(),
+        (2, 1) => day02::part1(lines())?.into(),
+        (2, 2) => day02::part2(lines())?.into(),
+        (3, 1) => day03::part1(bytes()).into(),
+        (3, 2) => day03::part2(bytes()).into(),
+        (4, 1) => day04::part1(lines())?.into(),
+        (4, 2) => day04::part2(lines())?.into(),
+        (5, 1) => day05::part1(input)?.into(),
+        (5, 2) => day05::part2(input)?.into(),
+        (6, 1) => day06::part1(input).into(),
+        (6, 2) => day06::part2(input).into(),
+        (7, 1) => day07::part1(lines()).into(),
+        (7, 2) => day07::part2(lines()).into(),
+        (8, 1) => day08::part1(input)?.into(),
+        (8, 2) => day08::part2(input)?.into(),
+        (9, 1) => day09::part1(lines()).into(),
+        (9, 2) => day09::part2(lines()).into(),
+        (10, 1) => day10::part1(bytes()).into(),
+        (10, 2) => day10::part2(bytes()).into(),
+        (11, 1) => day11::part1(bytes()).into(),
+        (11, 2) => day11::part2(bytes()).into(),
+        (12, 1) => day12::part1(input)?.into(),
+        (12, 2) => day12::part2(input)?.into(),
+        (13, 1) => day13::part1(lines()).into(),
+        (13, 2) => day13::part2(lines()).into(),
+        (14, 1) => day14::part1(bytes()).into(),
+        (14, 2) => day14::part2(bytes()).into(),
+        (15, 1) => day15::part1(lines()).into(),
+        (15, 2) => day15::part2(input).into(),
+        (16, 1) => day16::part1(bytes()).into(),
+        (16, 2) => day16::part2(bytes()).into(),
+        (17, 1) => day17::part1(bytes())?.into(),
+        (17, 2) => day17::part2(bytes())?.into(),
+        (18, 1) => day18::part1(lines()).into(),
+        (18, 2) => day18::part2(lines()).into(),
+        (19, 1) => day19::part1(input)?.into(),
+        (19, 2) => day19::part2(input)?.into(),
+        (20, 1) => day20::part1(input)?.into(),
+        (20, 2) => day20::part2(input)?.into(),
+        (21, 1) => day21::part1(bytes())?.into(),
+        (21, 2) => day21::part2(bytes())?.into(),
+        (22, 1) => day22::part1(lines()).into(),
+        (22, 2) => day22::part2(lines()).into(),
+        (23, 1) => day23::part1(bytes())?.into(),
+        (23, 2) => day23::part2(bytes())?.into(),
+        (24, 1) => day24::part1(input).into(),
+        (24, 2) => day24::part2(input)?.into(),
+        (25, 1) => day25::part1(input)?.into(),
+        _ => anyhow::bail!("day {day} has no part {part}"),
+    })
+}
+
+/// Like `solve`, but with `params` in place of the puzzle constants where a part has some.
+pub fn solve_with(day: usize, part: u8, input: &str, params: &[u64]) -> anyhow::Result<Answer> {
+    let expected = |count: usize| {
+        anyhow::ensure!(
+            params.len() == count,
+            "day {day} part {part} takes {count} parameters, got {}",
+            params.len()
+        );
+        Ok(())
+    };
+    match (day, part) {
+        (11, 2) => {
+            expected(1)?;
+            let input = input.as_bytes().to_vec();
+            Ok(day11::sum_distances(input, params[0] as isize).into())
+        }
+        (21, 1 | 2) => {
+            expected(1)?;
+            day21::reachable_plots(input, params[0]).map(Answer::from)
+        }
+        (24, 1) => {
+            expected(2)?;
+            let (min, max) = (params[0] as i128, params[1] as i128);
+            Ok(day24::crossings(input, min, max).into())
+        }
+        _ => anyhow::bail!("day {day} part {part} takes no parameters"),
+    }
+}
+
+/// Runs every part of `day`, parsing only once for days implementing `Solution`. `mark` is
+/// called as each phase starts and once more when the last one ends, so callers can measure
+/// them.
+pub fn run_day(
+    day: usize,
+    input: &str,
+    mut mark: impl FnMut(Phase),
+) -> anyhow::Result<Vec<Answer>> {
+    match day {
+        5 => run_solution::<Day05>(input, &mut mark),
+        8 => run_solution::<Day08>(input, &mut mark),
+        12 => run_solution::<Day12>(input, &mut mark),
+        19 => run_solution::<Day19>(input, &mut mark),
+        20 => run_solution::<Day20>(input, &mut mark),
+        _ => {
+            let mut answers = vec![];
+            for part in 1..=if day == 25 { 1 } else { 2 } {
+                mark(Phase::Part(part));
+                answers.push(solve(day, part, input)?);
+            }
+            mark(Phase::Done);
+            Ok(answers)
+        }
+    }
+}
+
+/// The puzzle-specific properties `day` relies on, and whether each holds for `input`.
+pub fn check_assumptions(day: usize, input: &str) -> anyhow::Result<Vec<(&'static str, bool)>> {
+    Ok(match day {
+        8 => day08::check_assumptions(input),
+        10 => day10::check_assumptions(input),
+        20 => day20::check_assumptions(input),
+        21 => day21::check_assumptions(input),
+        _ => anyhow::bail!("day {day} makes no assumptions about its input"),
+    })
+}
@@ -1,69 +1,219 @@
-use std::env::args;
+use std::{
+    env::args,
+    fs,
+    io::{self, Write},
+    time::{Duration, Instant},
+};
 
+use aoc_2023::{solution::Phase, *};
 use aoc_framework::*;
 
-mod day01;
-use day01::Day01;
+#[cfg(feature = "profile")]
+#[global_allocator]
+static ALLOC: profile::CountingAlloc = profile::CountingAlloc;
 
-mod day02;
-use day02::Day02;
-
-mod day03;
-use day03::Day03;
-
-mod day04;
-use day04::Day04;
-
-mod day05;
-use day05::Day05;
-
-mod day06;
-use day06::Day06;
-
-mod day07;
-use day07::Day07;
-
-mod day08;
-use day08::Day08;
-
-mod day09;
-use day09::Day09;
-
-mod day10;
-use day10::Day10;
-
-mod day11;
-use day11::Day11;
-
-mod day12;
-use day12::Day12;
-
-mod day13;
-use day13::Day13;
-
-mod day14;
-use day14::Day14;
-
-mod day15;
-use day15::Day15;
+/// Runs every example, or only those for `day`, and fails if any answer is wrong.
+fn run_examples(day: Option<usize>) -> anyhow::Result<()> {
+    let (mut passed, mut failed) = (0, 0);
+    for d in (1..=25).filter(|&d| day.is_none_or(|day| day == d)) {
+        let examples = examples::EXAMPLES
+            .iter()
+            .filter(|e| e.day == d)
+            .collect_vec();
+        for part in 1..=if d == 25 { 1 } else { 2 } {
+            let mut ran = false;
+            for (n, example) in examples.iter().enumerate() {
+                let input = example.input.strip_prefix('\n').unwrap_or(example.input);
+                for &(_, params, expected) in example.checks.iter().filter(|c| c.0 == part) {
+                    ran = true;
+                    let with = match params {
+                        [] => String::new(),
+                        _ => format!(" with {}", params.iter().join(", ")),
+                    };
+                    print!("day {d:02} part {part}, example {}{with}: ", n + 1);
+                    let result = match params {
+                        [] => solve(d, part, input),
+                        _ => solve_with(d, part, input, params),
+                    };
+                    match result {
+                        Ok(answer) if answer == Answer::from(expected) => {
+                            passed += 1;
+                            println!("{answer} ok");
+                        }
+                        Ok(answer) => {
+                            failed += 1;
+                            println!("{answer}, expected {expected} FAILED");
+                        }
+                        Err(e) => {
+                            failed += 1;
+                            println!("{e} FAILED");
+                        }
+                    }
+                }
+            }
+            if !ran {
+                println!("day {d:02} part {part}: no example");
+            }
+        }
+    }
+    println!("{passed} passed, {failed} failed");
+    anyhow::ensure!(failed == 0, "{failed} examples failed");
+    Ok(())
+}
 
-mod day16;
-use day16::Day16;
+/// Reports which of the puzzle-specific properties a day relies on hold for `input`.
+fn validate(day: usize, input: &str) -> anyhow::Result<()> {
+    let checks = check_assumptions(day, input)?;
+    for (assumption, holds) in &checks {
+        println!("{:5} {assumption}", if *holds { "holds" } else { "FAILS" });
+    }
+    let failed = checks.iter().filter(|(_, holds)| !holds).count();
+    anyhow::ensure!(
+        failed == 0,
+        "{failed} of {} assumptions don't hold",
+        checks.len()
+    );
+    Ok(())
+}
 
-mod day17;
-use day17::Day17;
+/// Runs `day` on `input` and reports how long parsing and each part took.
+fn time(day: usize, input: &str) -> anyhow::Result<()> {
+    let mut timings: Vec<(Phase, Duration)> = vec![];
+    let mut started = None;
+    let answers = run_day(day, input, |phase| {
+        if let Some((last, start)) = started.replace((phase, Instant::now())) {
+            timings.push((last, start.elapsed()));
+        }
+    })?;
+    let mut answers = answers.iter();
+    for (phase, elapsed) in timings {
+        let answer = match phase {
+            Phase::Part(_) => answers.next().map(|a| a.to_string()),
+            _ => None,
+        };
+        println!(
+            "{:8} {elapsed:>12.3?}  {}",
+            phase.name(),
+            answer.unwrap_or_default()
+        );
+    }
+    Ok(())
+}
 
-mod day18;
-use day18::Day18;
+/// Profiles each `<day> <input>` pair and prints time, allocations and peak heap per phase.
+fn profile(args: &[String]) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        cfg!(feature = "profile"),
+        "allocations are only counted when built with `--features profile`"
+    );
+    anyhow::ensure!(
+        !args.is_empty() && args.len().is_multiple_of(2),
+        "expected <day> <input> pairs"
+    );
+    println!(
+        "{:>3}  {:8} {:>12} {:>10} {:>12}",
+        "day", "phase", "time", "allocs", "peak bytes"
+    );
+    for pair in args.chunks(2) {
+        let day = pair[0].parse()?;
+        let input = read_input(&pair[1..])?;
+        let (_, phases) = profile::profile_day(day, &input)?;
+        for p in phases {
+            println!(
+                "{day:>3}  {:8} {:>12.3?} {:>10} {:>12}",
+                p.phase.name(),
+                p.time,
+                p.allocations,
+                p.peak_bytes
+            );
+        }
+    }
+    Ok(())
+}
 
-mod day19;
-use day19::Day19;
+// `-` reads the input from stdin
+fn read_input(args: &[String]) -> anyhow::Result<String> {
+    match args.first().map(String::as_str) {
+        None => anyhow::bail!("missing input path"),
+        Some("-") => Ok(io::read_to_string(io::stdin())?),
+        Some(path) => Ok(fs::read_to_string(path)?),
+    }
+}
 
-mod day20;
-use day20::Day20;
+/// Solves every part of `day` for an input given on the command line instead of the puzzle's.
+fn run_with_input(day: usize, input: &str) -> anyhow::Result<()> {
+    for (part, answer) in run_day(day, input, |_| {})?.iter().enumerate() {
+        println!("day {day:02} part {}: {answer}", part + 1);
+    }
+    Ok(())
+}
 
-mod day21;
-use day21::Day21;
+fn run_tool(day: usize, tool: &str, args: &[String]) -> anyhow::Result<()> {
+    match (day, tool) {
+        (1, "tokens") => {
+            let language = args.get(1).map_or("english", |l| l.as_str());
+            print!("{}", day01::list_tokens(&read_input(args)?, language)?)
+        }
+        (2, "feasible") => {
+            let Some(bag) = args.get(1) else {
+                anyhow::bail!("missing bag, e.g. \"12 red, 13 green, 14 blue\"");
+            };
+            print!("{}", day02::feasible_games(&read_input(args)?, bag)?)
+        }
+        (2, "bags") => print!("{}", day02::minimal_bags(&read_input(args)?)?),
+        (3, "numbers") => print!("{}", day03::list_numbers(read_input(args)?.into_bytes())),
+        (3, "adjacent") => {
+            let input = read_input(args)?.into_bytes();
+            let symbol = args.get(1).and_then(|s| s.bytes().next());
+            print!("{}", day03::list_adjacent(input, symbol))
+        }
+        (3, "gears") => {
+            let input = read_input(args)?.into_bytes();
+            let symbol = args.get(1).and_then(|s| s.bytes().next()).unwrap_or(b'*');
+            let count = args.get(2).map(|n| n.parse()).transpose()?.unwrap_or(2);
+            print!("{}", day03::list_gears(input, symbol, count))
+        }
+        (4, "copies") => print!("{}", day04::list_copies(&read_input(args)?)?),
+        (17, "render") => print!("{}", day17::render(read_input(args)?.into_bytes())),
+        (18, "ppm") => io::stdout().write_all(&day18::export_ppm(&read_input(args)?))?,
+        (18, "svg") => print!("{}", day18::export_svg(&read_input(args)?)),
+        (19, "dot") => print!("{}", day19::export_dot(&read_input(args)?)?),
+        (19, "accepted") => print!("{}", day19::list_accepted(&read_input(args)?)?),
+        (19, "classify") => {
+            let csv = read_input(args.get(1..).unwrap_or_default())?;
+            print!("{}", day19::classify_csv(&read_input(args)?, &csv)?)
+        }
+        (19, "count") => println!(
+            "{}",
+            day19::count_accepted(&read_input(args)?, args.get(1..).unwrap_or_default())?
+        ),
+        (20, "trace") => {
+            let presses = args.get(1).map(|n| n.parse()).transpose()?.unwrap_or(1);
+            print!("{}", day20::trace(&read_input(args)?, presses))
+        }
+        (20, "analyse") => print!("{}", day20::analyse(&read_input(args)?)?),
+        (21, "steps") => {
+            let Some(steps) = args.get(1) else {
+                anyhow::bail!("missing step count");
+            };
+            println!(
+                "{}",
+                day21::reachable_plots(&read_input(args)?, steps.parse()?)?
+            )
+        }
+        (24, "crossings") => {
+            let (Some(min), Some(max)) = (args.get(1), args.get(2)) else {
+                anyhow::bail!("missing test area bounds");
+            };
+            let input = read_input(args)?;
+            println!("{}", day24::crossings(&input, min.parse()?, max.parse()?))
+        }
+        (24, "rock") => print!("{}", day24::describe_rock(&read_input(args)?)?),
+        (25, "cut") => print!("{}", day25::describe_cut(&read_input(args)?)?),
+        _ => anyhow::bail!("no tool named {tool:?} for day {day}"),
+    }
+    Ok(())
+}
 
 fn main() -> anyhow::Result<()> {
     let days = [
@@ -88,16 +238,46 @@ fn main() -> anyhow::Result<()> {
         Day19::run,
         Day20::run,
         Day21::run,
+        Day22::run,
+        Day23::run,
+        Day24::run,
+        Day25::run,
     ];
 
     let token = std::env::var("AOC_TOKEN").ok();
 
-    if let Some(day) = args()
-        .nth(1)
-        .and_then(|arg| arg.parse::<usize>().ok())
-        .and_then(|day| days.get(day - 1))
-    {
-        day(token.as_deref());
+    let args = args().collect_vec();
+    if args.get(1).is_some_and(|a| a == "--examples") {
+        return run_examples(args.get(2).map(|d| d.parse()).transpose()?);
+    }
+    if args.get(1).is_some_and(|a| a == "--profile") {
+        return profile(&args[2..]);
+    }
+    if let [_, cmd, day, input_args @ ..] = &args[..] {
+        match cmd.as_str() {
+            "validate" => return validate(day.parse()?, &read_input(input_args)?),
+            "time" => return time(day.parse()?, &read_input(input_args)?),
+            _ => {}
+        }
+    }
+    match &args[..] {
+        [_, day, flag, input_args @ ..] if flag == "--input" => {
+            return run_with_input(day.parse()?, &read_input(input_args)?)
+        }
+        [_, day, stdin] if stdin == "-" => {
+            return run_with_input(day.parse()?, &read_input(&args[2..])?)
+        }
+        _ => {}
+    }
+    if let [_, day, tool, tool_args @ ..] = &args[..] {
+        return run_tool(day.parse()?, tool, tool_args);
+    }
+
+    if let Some(day) = args.get(1).and_then(|arg| arg.parse::<usize>().ok()) {
+        let Some(run) = day.checked_sub(1).and_then(|d| days.get(d)) else {
+            anyhow::bail!("no day {day}, expected 1 to 25");
+        };
+        run(token.as_deref());
         return Ok(());
     }
 
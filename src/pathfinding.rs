@@ -0,0 +1,205 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    hash::Hash,
+};
+
+struct KeyedBy<T, O: Ord> {
+    v: T,
+    o: O,
+}
+
+impl<T, O: Ord> PartialEq for KeyedBy<T, O> {
+    fn eq(&self, other: &Self) -> bool {
+        self.o == other.o
+    }
+}
+
+impl<T, O: Ord> Eq for KeyedBy<T, O> {}
+
+impl<T, O: Ord> PartialOrd for KeyedBy<T, O> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, O: Ord> Ord for KeyedBy<T, O> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.o.cmp(&other.o)
+    }
+}
+
+/// Best known cost for each state, along with the state it was reached from.
+pub trait Visited<S> {
+    fn get(&self, state: &S) -> Option<(u64, Option<S>)>;
+    fn insert(&mut self, state: S, cost: u64, came_from: Option<S>);
+
+    fn path_to(&self, last: S) -> Vec<S>
+    where
+        S: Copy,
+    {
+        let mut path = vec![last];
+        let mut cur = last;
+        while let Some((_, Some(prev))) = self.get(&cur) {
+            path.push(prev);
+            cur = prev;
+        }
+        path.reverse();
+        path
+    }
+}
+
+impl<S: Copy + Hash + Eq> Visited<S> for HashMap<S, (u64, Option<S>)> {
+    fn get(&self, state: &S) -> Option<(u64, Option<S>)> {
+        HashMap::get(self, state).copied()
+    }
+
+    fn insert(&mut self, state: S, cost: u64, came_from: Option<S>) {
+        HashMap::insert(self, state, (cost, came_from));
+    }
+}
+
+/// `Visited` storage for states that map to a dense index, e.g. grid positions.
+pub struct Dense<S, F> {
+    entries: Vec<Option<(u64, Option<S>)>>,
+    index: F,
+}
+
+impl<S: Copy, F: Fn(&S) -> usize> Dense<S, F> {
+    pub fn new(len: usize, index: F) -> Self {
+        Dense {
+            entries: vec![None; len],
+            index,
+        }
+    }
+}
+
+impl<S: Copy, F: Fn(&S) -> usize> Visited<S> for Dense<S, F> {
+    fn get(&self, state: &S) -> Option<(u64, Option<S>)> {
+        self.entries[(self.index)(state)]
+    }
+
+    fn insert(&mut self, state: S, cost: u64, came_from: Option<S>) {
+        let ndx = (self.index)(&state);
+        self.entries[ndx] = Some((cost, came_from));
+    }
+}
+
+pub struct Path<S> {
+    pub cost: u64,
+    pub states: Vec<S>,
+}
+
+/// A* search from any of `starts` to the first state satisfying `is_goal`.
+/// `neighbors` yields each reachable state with the cost of getting there, and
+/// `heuristic` must never overestimate the remaining cost (use `|_| 0` for Dijkstra).
+pub fn a_star<S, V, I>(
+    starts: impl IntoIterator<Item = S>,
+    visited: &mut V,
+    mut neighbors: impl FnMut(&S) -> I,
+    heuristic: impl Fn(&S) -> u64,
+    is_goal: impl Fn(&S) -> bool,
+) -> Option<Path<S>>
+where
+    S: Copy,
+    V: Visited<S>,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    let mut open_set = BinaryHeap::new();
+    for start in starts {
+        visited.insert(start, 0, None);
+        open_set.push(KeyedBy {
+            v: (start, 0),
+            o: Reverse(heuristic(&start)),
+        });
+    }
+
+    while let Some(KeyedBy {
+        v: (current, cost), ..
+    }) = open_set.pop()
+    {
+        if visited.get(&current).is_some_and(|(best, _)| best < cost) {
+            // stale entry, a cheaper way to this state was already expanded
+            continue;
+        }
+        if is_goal(&current) {
+            return Some(Path {
+                cost,
+                states: visited.path_to(current),
+            });
+        }
+        for (next, step_cost) in neighbors(&current) {
+            let tentative_score = cost + step_cost;
+            if visited
+                .get(&next)
+                .is_some_and(|(best, _)| best <= tentative_score)
+            {
+                continue;
+            }
+            visited.insert(next, tentative_score, Some(current));
+            open_set.push(KeyedBy {
+                v: (next, tentative_score),
+                o: Reverse(tentative_score + heuristic(&next)),
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Pos = (usize, usize);
+
+    // digits are the cost of entering a cell, `#` is a wall
+    fn search(grid: &[&str], start: Pos, goal: Pos) -> Option<Path<Pos>> {
+        let (w, h) = (grid[0].len(), grid.len());
+        let cell = |(x, y): Pos| grid[y].as_bytes()[x];
+        let mut visited = Dense::new(w * h, |&(x, y): &Pos| y * w + x);
+        a_star(
+            [start],
+            &mut visited,
+            |&(x, y)| {
+                [
+                    (x + 1, y),
+                    (x.wrapping_sub(1), y),
+                    (x, y + 1),
+                    (x, y.wrapping_sub(1)),
+                ]
+                .into_iter()
+                .filter(|&(x, y)| x < w && y < h && cell((x, y)) != b'#')
+                .map(|p| (p, (cell(p) - b'0') as u64))
+                .collect::<Vec<_>>()
+            },
+            |&(x, y)| (x.abs_diff(goal.0) + y.abs_diff(goal.1)) as u64,
+            |&p| p == goal,
+        )
+    }
+
+    #[test]
+    fn cheapest_path() {
+        let path = search(&["131", "1#1", "111"], (0, 0), (2, 2)).unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.states, [(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn cheaper_detour() {
+        let path = search(&["191", "111"], (0, 0), (2, 0)).unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.states.len(), 5);
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        assert!(search(&["1#1", "##1"], (0, 0), (2, 1)).is_none());
+    }
+
+    #[test]
+    fn start_is_goal() {
+        let path = search(&["1"], (0, 0), (0, 0)).unwrap();
+        assert_eq!(path.cost, 0);
+        assert_eq!(path.states, [(0, 0)]);
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering::Relaxed},
+    time::{Duration, Instant},
+};
+
+use aoc_framework::*;
+
+use crate::{run_day, solution::Phase, Answer};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static HEAP: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// Wraps the system allocator, counting allocations and tracking the heap size. The counts are
+/// only meaningful when it is installed with `#[global_allocator]`, which the binary does
+/// with the `profile` feature.
+pub struct CountingAlloc;
+
+fn grow(size: usize) {
+    let heap = HEAP.fetch_add(size, Relaxed) + size;
+    PEAK.fetch_max(heap, Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Relaxed);
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Relaxed);
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        HEAP.fetch_sub(layout.size(), Relaxed);
+    }
+
+    // a reallocation counts as one more allocation, since growing a Vec or HashMap is exactly
+    // what we want to see
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new.is_null() {
+            ALLOCATIONS.fetch_add(1, Relaxed);
+            HEAP.fetch_sub(layout.size(), Relaxed);
+            grow(new_size);
+        }
+        new
+    }
+}
+
+/// Time and heap usage of one phase of a day.
+pub struct PhaseProfile {
+    pub phase: Phase,
+    pub time: Duration,
+    pub allocations: usize,
+    /// Largest heap size reached during the phase, above what was already in use when it started.
+    pub peak_bytes: usize,
+}
+
+/// Runs every part of `day` like `run_day`, measuring each phase on the way.
+pub fn profile_day(day: usize, input: &str) -> anyhow::Result<(Vec<Answer>, Vec<PhaseProfile>)> {
+    // reserved up front so recording a phase doesn't allocate inside the next one
+    let mut phases = Vec::with_capacity(4);
+    let mut started: Option<(Phase, Instant, usize, usize)> = None;
+    let answers = run_day(day, input, |phase| {
+        let (allocations, heap) = (ALLOCATIONS.load(Relaxed), HEAP.load(Relaxed));
+        if let Some((last, start, allocations_before, heap_before)) = started {
+            phases.push(PhaseProfile {
+                phase: last,
+                time: start.elapsed(),
+                allocations: allocations - allocations_before,
+                peak_bytes: PEAK.load(Relaxed).saturating_sub(heap_before),
+            });
+        }
+        PEAK.store(heap, Relaxed);
+        started = Some((phase, Instant::now(), allocations, heap));
+    })?;
+    Ok((answers, phases))
+}
@@ -0,0 +1,53 @@
+use aoc_framework::*;
+
+use crate::Answer;
+
+/// A day whose input is parsed once and then shared by both parts.
+pub trait Solution {
+    type Parsed;
+    type Output: Into<Answer>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed>;
+    fn part1(parsed: &Self::Parsed) -> anyhow::Result<Self::Output>;
+    fn part2(parsed: &Self::Parsed) -> anyhow::Result<Self::Output>;
+}
+
+/// Parses and solves one part in a single call, for the `#[aoc]` functions of `Solution` days.
+pub fn parse_and_solve<S: Solution>(
+    input: &str,
+    part: fn(&S::Parsed) -> anyhow::Result<S::Output>,
+) -> anyhow::Result<S::Output> {
+    part(&S::parse(input)?)
+}
+
+/// What `run_day` is about to do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Part(u8),
+    Done,
+}
+
+impl Phase {
+    pub fn name(self) -> String {
+        match self {
+            Phase::Parse => "parse".to_string(),
+            Phase::Part(part) => format!("part {part}"),
+            Phase::Done => "done".to_string(),
+        }
+    }
+}
+
+pub(crate) fn run_solution<S: Solution>(
+    input: &str,
+    mark: &mut impl FnMut(Phase),
+) -> anyhow::Result<Vec<Answer>> {
+    mark(Phase::Parse);
+    let parsed = S::parse(input)?;
+    mark(Phase::Part(1));
+    let part1 = S::part1(&parsed)?.into();
+    mark(Phase::Part(2));
+    let part2 = S::part2(&parsed)?.into();
+    mark(Phase::Done);
+    Ok(vec![part1, part2])
+}